@@ -0,0 +1,104 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use cqrs_es::{EventEnvelope, Query};
+
+use crate::{BankAccount, BankAccountEvent};
+
+/// Groups accounts by the branch they currently belong to, for branch-level reporting.
+#[derive(Default)]
+pub struct BranchReportQuery {
+    branches: Mutex<BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl BranchReportQuery {
+    /// The accounts currently assigned to `branch_id`, in id order.
+    pub fn accounts_in(&self, branch_id: &str) -> Vec<String> {
+        let branches = self.branches.lock().unwrap();
+        branches
+            .get(branch_id)
+            .map(|accounts| accounts.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl Query<BankAccount> for BranchReportQuery {
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<BankAccount>]) {
+        let mut branches = self.branches.lock().unwrap();
+        for event in events {
+            match &event.payload {
+                BankAccountEvent::AccountOpened {
+                    branch_id: Some(branch_id),
+                    ..
+                } => {
+                    branches
+                        .entry(branch_id.clone())
+                        .or_default()
+                        .insert(aggregate_id.to_string());
+                }
+                BankAccountEvent::BranchTransferred { old, new } => {
+                    if let Some(accounts) = old.as_ref().and_then(|old| branches.get_mut(old)) {
+                        accounts.remove(aggregate_id);
+                    }
+                    branches
+                        .entry(new.clone())
+                        .or_default()
+                        .insert(aggregate_id.to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn envelope(sequence: usize, payload: BankAccountEvent) -> EventEnvelope<BankAccount> {
+        EventEnvelope {
+            aggregate_id: "acct-1".to_string(),
+            sequence,
+            payload,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_follows_branch_transfer() {
+        let query = BranchReportQuery::default();
+        query
+            .dispatch(
+                "acct-1",
+                &[envelope(
+                    1,
+                    BankAccountEvent::AccountOpened {
+                        account_id: "acct-1".to_string(),
+                        max_holds: None,
+                        first_withdrawal_allowed_after: None,
+                        branch_id: Some("branch-1".to_string()),
+                    },
+                )],
+            )
+            .await;
+        assert_eq!(query.accounts_in("branch-1"), vec!["acct-1".to_string()]);
+
+        query
+            .dispatch(
+                "acct-1",
+                &[envelope(
+                    2,
+                    BankAccountEvent::BranchTransferred {
+                        old: Some("branch-1".to_string()),
+                        new: "branch-2".to_string(),
+                    },
+                )],
+            )
+            .await;
+        assert!(query.accounts_in("branch-1").is_empty());
+        assert_eq!(query.accounts_in("branch-2"), vec!["acct-1".to_string()]);
+    }
+}
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use cqrs_es::{EventEnvelope, Query};
+use tokio::sync::broadcast;
+
+use crate::{BankAccount, BankAccountEvent};
+
+/// Publishes committed events to in-process subscribers such as live dashboards.
+pub struct BroadcastQuery {
+    sender: broadcast::Sender<BankAccountEvent>,
+}
+
+impl BroadcastQuery {
+    /// `capacity` is how many events a slow subscriber may fall behind before it lags.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BankAccountEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[async_trait]
+impl Query<BankAccount> for BroadcastQuery {
+    async fn dispatch(&self, _aggregate_id: &str, events: &[EventEnvelope<BankAccount>]) {
+        for event in events {
+            // Sending only fails when nobody is subscribed, which is not an error here.
+            let _ = self.sender.send(event.payload.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn envelope(sequence: usize, payload: BankAccountEvent) -> EventEnvelope<BankAccount> {
+        EventEnvelope {
+            aggregate_id: "acct-1".to_string(),
+            sequence,
+            payload,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_receives_events_in_order() {
+        let query = BroadcastQuery::new(16);
+        let mut receiver = query.subscribe();
+        let deposited = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let withdrew = BankAccountEvent::CustomerWithdrewCash {
+            amount: 50.0,
+            balance: 150.0,
+        };
+
+        query
+            .dispatch(
+                "acct-1",
+                &[
+                    envelope(1, deposited.clone()),
+                    envelope(2, withdrew.clone()),
+                ],
+            )
+            .await;
+
+        assert_eq!(receiver.recv().await.unwrap(), deposited);
+        assert_eq!(receiver.recv().await.unwrap(), withdrew);
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::SystemTime;
+
+use crate::date::Date;
+
+/// Source of the current time for time-based command handling.
+pub trait Clock: Sync + Send {
+    fn now(&self) -> SystemTime;
+
+    fn today(&self) -> Date {
+        Date::from_system_time(self.now())
+    }
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock stopped at one instant, for deterministic tests.
+pub struct FixedClock(pub SystemTime);
+
+impl FixedClock {
+    /// A clock stopped at midnight UTC on `date`.
+    pub fn on(date: Date) -> Self {
+        FixedClock(date.start_of_day())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
@@ -0,0 +1,48 @@
+use cqrs_es::{Aggregate, DomainEvent, EventEnvelope};
+use serde_json::{json, Value};
+
+use crate::BankAccount;
+
+/// Renders a committed event as a CloudEvents 1.0 structured-mode JSON document.
+pub fn to_cloudevent(envelope: &EventEnvelope<BankAccount>) -> Value {
+    json!({
+        "specversion": "1.0",
+        "id": format!("{}-{}", envelope.aggregate_id, envelope.sequence),
+        "source": format!("/{}", BankAccount::aggregate_type()),
+        "type": envelope.payload.event_type(),
+        "subject": envelope.aggregate_id,
+        "datacontenttype": "application/json",
+        "data": envelope.payload,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::BankAccountEvent;
+
+    #[test]
+    fn test_to_cloudevent() {
+        let payload = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let envelope = EventEnvelope::<BankAccount> {
+            aggregate_id: "acct-1".to_string(),
+            sequence: 3,
+            payload: payload.clone(),
+            metadata: HashMap::new(),
+        };
+
+        let cloudevent = to_cloudevent(&envelope);
+
+        assert_eq!(cloudevent["specversion"], "1.0");
+        assert_eq!(cloudevent["id"], "acct-1-3");
+        assert_eq!(cloudevent["source"], "/Account");
+        assert_eq!(cloudevent["type"], payload.event_type());
+        assert_eq!(cloudevent["subject"], "acct-1");
+        assert_eq!(cloudevent["data"], serde_json::to_value(&payload).unwrap());
+    }
+}
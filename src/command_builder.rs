@@ -0,0 +1,171 @@
+use crate::{BankAccountCommand, BankAccountError, PositiveAmount};
+
+/// Shorthand constructors for `BankAccountCommand`. Each is a plain associated function
+/// returning the finished command rather than a chainable builder, and each checks its
+/// arguments the way the command handler would, so an invalid command is never built.
+pub struct CommandBuilder;
+
+impl CommandBuilder {
+    /// Fails if `account_id` is empty.
+    pub fn open(account_id: &str) -> Result<BankAccountCommand, BankAccountError> {
+        if account_id.is_empty() {
+            return Err("invalid account id".into());
+        }
+        Ok(BankAccountCommand::OpenAccount {
+            account_id: account_id.to_string(),
+            max_holds: None,
+            holding_period_days: None,
+            branch_id: None,
+        })
+    }
+
+    /// Fails if `amount` is not positive, since a deposit can't carry one.
+    pub fn deposit(amount: f64) -> Result<BankAccountCommand, BankAccountError> {
+        Ok(BankAccountCommand::DepositMoney {
+            amount: PositiveAmount::new(amount)?,
+        })
+    }
+
+    /// Fails if `amount` is not positive, since a withdrawal can't carry one.
+    pub fn withdraw(amount: f64) -> Result<BankAccountCommand, BankAccountError> {
+        Ok(BankAccountCommand::WithdrawMoney {
+            amount: PositiveAmount::new(amount)?,
+            category: None,
+            mcc: None,
+        })
+    }
+
+    /// Fails if `amount` is not positive or `to_account_id` is empty.
+    pub fn transfer_to(
+        to_account_id: &str,
+        amount: f64,
+    ) -> Result<BankAccountCommand, BankAccountError> {
+        let amount = PositiveAmount::new(amount)?.value();
+        if to_account_id.is_empty() {
+            return Err("invalid transfer account".into());
+        }
+        Ok(BankAccountCommand::TransferMoney {
+            to_account_id: to_account_id.to_string(),
+            amount,
+        })
+    }
+
+    /// Fails if `amount` is not positive.
+    pub fn write_check(
+        check_number: &str,
+        amount: f64,
+    ) -> Result<BankAccountCommand, BankAccountError> {
+        Ok(BankAccountCommand::WriteCheck {
+            check_number: check_number.to_string(),
+            amount: PositiveAmount::new(amount)?.value(),
+            category: None,
+            mcc: None,
+        })
+    }
+
+    /// Fails if `limit` is negative or not a number.
+    pub fn set_category_budget(
+        category: &str,
+        limit: f64,
+    ) -> Result<BankAccountCommand, BankAccountError> {
+        if !(limit.is_finite() && limit >= 0_f64) {
+            return Err("budget limit must not be negative".into());
+        }
+        Ok(BankAccountCommand::SetCategoryBudget {
+            category: category.to_string(),
+            limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builders_match_struct_syntax() {
+        assert_eq!(
+            CommandBuilder::open("acct-1"),
+            Ok(BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: None,
+            })
+        );
+        let amount = PositiveAmount::new(200.0).unwrap();
+        assert_eq!(
+            CommandBuilder::deposit(200.0),
+            Ok(BankAccountCommand::DepositMoney { amount })
+        );
+        assert_eq!(
+            CommandBuilder::withdraw(200.0),
+            Ok(BankAccountCommand::WithdrawMoney {
+                amount,
+                category: None,
+                mcc: None,
+            })
+        );
+        assert_eq!(
+            CommandBuilder::transfer_to("acct-2", 10.0),
+            Ok(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 10.0,
+            })
+        );
+        assert_eq!(
+            CommandBuilder::write_check("1170", 25.0),
+            Ok(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 25.0,
+                category: None,
+                mcc: None,
+            })
+        );
+        assert_eq!(
+            CommandBuilder::set_category_budget("groceries", 100.0),
+            Ok(BankAccountCommand::SetCategoryBudget {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_builders_reject_non_positive_amounts() {
+        assert_eq!(
+            CommandBuilder::deposit(0.0),
+            Err(BankAccountError("amount must be positive: 0".to_string()))
+        );
+        assert_eq!(
+            CommandBuilder::withdraw(-50.0),
+            Err(BankAccountError("amount must be positive: -50".to_string()))
+        );
+        assert_eq!(
+            CommandBuilder::transfer_to("acct-1", -10.0),
+            Err(BankAccountError("amount must be positive: -10".to_string()))
+        );
+        assert_eq!(
+            CommandBuilder::write_check("1170", f64::NAN),
+            Err(BankAccountError("amount must be positive: NaN".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_builders_reject_invalid_arguments() {
+        assert_eq!(
+            CommandBuilder::open(""),
+            Err(BankAccountError("invalid account id".to_string()))
+        );
+        assert_eq!(
+            CommandBuilder::transfer_to("", 10.0),
+            Err(BankAccountError("invalid transfer account".to_string()))
+        );
+        assert_eq!(
+            CommandBuilder::set_category_budget("groceries", -1.0),
+            Err(BankAccountError(
+                "budget limit must not be negative".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use std::fmt::Display;
+use std::time::SystemTime;
+
+use cqrs_es::Aggregate;
+use serde_json::Value;
+
+use crate::{BankAccount, BankAccountEvent};
+
+/// Account state that compaction never preserves: the checkpoint renumbers the stream, so
+/// the event count starts over.
+const RENUMBERED_FIELDS: [&str; 1] = ["event_count"];
+
+/// Compaction would leave account state, named by `field`, different from the full replay.
+#[derive(Debug, PartialEq)]
+pub struct CompactionError {
+    pub field: String,
+}
+
+impl Display for CompactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "compaction would change {}", self.field)
+    }
+}
+
+impl std::error::Error for CompactionError {}
+
+/// Folds the balance-only events among the first `up_to` into a single `BalanceEstablished`
+/// checkpoint and appends the remaining events unchanged. Prefix events that set any other
+/// state, such as opening the account or placing a hold, are kept ahead of the checkpoint.
+/// The compacted stream must replay to the same state as the original, so compaction is
+/// refused when it would shift state keyed by sequence, such as interest postings, anywhere
+/// in the stream.
+pub fn compact(
+    events: &[BankAccountEvent],
+    up_to: usize,
+) -> Result<Vec<BankAccountEvent>, CompactionError> {
+    let up_to = up_to.min(events.len());
+    let (prefix, tail) = events.split_at(up_to);
+    if prefix.is_empty() {
+        return Ok(tail.to_vec());
+    }
+
+    let mut account = BankAccount::default();
+    let mut kept = Vec::new();
+    for event in prefix {
+        let before = account_state(&account);
+        account.apply(event.clone());
+        if account_state(&account) != before {
+            kept.push(event.clone());
+        }
+    }
+    kept.push(BankAccountEvent::BalanceEstablished {
+        balance: account.balance,
+    });
+    kept.extend_from_slice(tail);
+
+    // Dropping events renumbers everything after them, prefix and tail alike.
+    if let Some(field) = lost_field(&replay(events), &replay(&kept)) {
+        return Err(CompactionError { field });
+    }
+    Ok(kept)
+}
+
+/// The account's state other than its balance and the fields compaction renumbers.
+fn account_state(account: &BankAccount) -> Option<Value> {
+    let Ok(Value::Object(mut state)) = serde_json::to_value(account) else {
+        return None;
+    };
+    state.retain(|field, _| field != "balance" && !RENUMBERED_FIELDS.contains(&field.as_str()));
+    Some(Value::Object(state))
+}
+
+/// The first field, other than those compaction renumbers, on which the two accounts differ.
+fn lost_field(account: &BankAccount, checkpointed: &BankAccount) -> Option<String> {
+    let (Ok(Value::Object(account)), Ok(Value::Object(checkpointed))) = (
+        serde_json::to_value(account),
+        serde_json::to_value(checkpointed),
+    ) else {
+        return Some("unserializable state".to_string());
+    };
+    account
+        .iter()
+        .filter(|(field, _)| !RENUMBERED_FIELDS.contains(&field.as_str()))
+        .find(|(field, value)| checkpointed.get(*field) != Some(*value))
+        .map(|(field, _)| field.clone())
+}
+
+/// How many leading events committed before `cutoff` may be archived. Only events preceding
+/// a `BalanceSnapshotted` before the cutoff qualify, and only if replaying from that snapshot
+/// reconstructs the same account state as the full stream; otherwise nothing is archivable.
+pub fn archivable_before(events: &[(SystemTime, BankAccountEvent)], cutoff: SystemTime) -> usize {
+    let full = replay_events(events);
+    events
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, (committed_at, event))| {
+            *committed_at < cutoff && matches!(event, BankAccountEvent::BalanceSnapshotted { .. })
+        })
+        .map(|(index, _)| index)
+        .find(|index| lost_field(&full, &replay_events(&events[*index..])).is_none())
+        .unwrap_or(0)
+}
+
+fn replay(events: &[BankAccountEvent]) -> BankAccount {
+    let mut account = BankAccount::default();
+    for event in events {
+        account.apply(event.clone());
+    }
+    account
+}
+
+fn replay_events(events: &[(SystemTime, BankAccountEvent)]) -> BankAccount {
+    let mut account = BankAccount::default();
+    for (_, event) in events {
+        account.apply(event.clone());
+    }
+    account
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cqrs_es::test::TestFramework;
+
+    use crate::date::Date;
+    use crate::{BankAccountCommand, BankAccountError, BankAccountServices};
+
+    #[test]
+    fn test_compacted_stream_replays_to_same_balance() {
+        let events = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 25.0,
+                balance: 175.0,
+            },
+            BankAccountEvent::CustomerWroteCheck {
+                check_number: "1170".to_string(),
+                amount: 75.0,
+                balance: 100.0,
+            },
+        ];
+
+        let compacted = compact(&events, 2).unwrap();
+
+        assert_eq!(compacted.len(), 3);
+        assert_eq!(
+            compacted[0],
+            BankAccountEvent::BalanceEstablished { balance: 150.0 }
+        );
+        assert_eq!(&compacted[1..], &events[2..]);
+        assert_eq!(replay(&compacted).balance, replay(&events).balance);
+    }
+
+    fn opened_history() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: 80.0,
+                placed_at: day(1),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 25.0,
+                balance: 175.0,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 10.0,
+                balance: 185.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_compact_keeps_state_setting_events() {
+        let events = opened_history();
+
+        let compacted = compact(&events, 6).unwrap();
+
+        assert_eq!(
+            compacted,
+            vec![
+                events[0].clone(),
+                events[1].clone(),
+                events[4].clone(),
+                BankAccountEvent::BalanceEstablished { balance: 175.0 },
+                events[6].clone(),
+            ]
+        );
+        let (full, replayed) = (replay(&events), replay(&compacted));
+        assert_eq!(lost_field(&full, &replayed), None);
+        assert_eq!(replayed.balance, 185.0);
+        assert!(replayed.opened && replayed.kyc_verified);
+        assert_eq!(replayed.available_balance(), 105.0);
+    }
+
+    fn day(day: u32) -> SystemTime {
+        Date::from_ymd(2023, 5, day).unwrap().start_of_day()
+    }
+
+    fn dated_history() -> Vec<(SystemTime, BankAccountEvent)> {
+        vec![
+            (
+                day(1),
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 200.0,
+                    balance: 200.0,
+                },
+            ),
+            (
+                day(2),
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 50.0,
+                    balance: 150.0,
+                },
+            ),
+            (
+                day(3),
+                BankAccountEvent::BalanceSnapshotted {
+                    balance: 150.0,
+                    as_of: day(3),
+                },
+            ),
+            (
+                day(10),
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 25.0,
+                    balance: 175.0,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_archivable_before_snapshot() {
+        let events = dated_history();
+
+        let archivable = archivable_before(&events, day(20));
+
+        assert_eq!(archivable, 2);
+        let full = replay_events(&events);
+        let tail = replay_events(&events[archivable..]);
+        assert_eq!(lost_field(&full, &tail), None);
+        assert_eq!(tail.balance, 175.0);
+    }
+
+    #[test]
+    fn test_nothing_archivable_when_snapshot_loses_state() {
+        let mut events: Vec<_> = opened_history()
+            .into_iter()
+            .map(|event| (day(1), event))
+            .collect();
+        events.insert(
+            6,
+            (
+                day(2),
+                BankAccountEvent::BalanceSnapshotted {
+                    balance: 175.0,
+                    as_of: day(2),
+                },
+            ),
+        );
+
+        // The snapshot carries only the balance, so archiving ahead of it would drop the
+        // opening, the KYC check and the hold.
+        assert_eq!(archivable_before(&events, day(20)), 0);
+    }
+
+    #[test]
+    fn test_nothing_archivable_without_snapshot() {
+        let mut events = dated_history();
+        events.remove(2);
+
+        assert_eq!(archivable_before(&events, day(20)), 0);
+        // A snapshot taken after the cutoff does not count either.
+        assert_eq!(archivable_before(&dated_history(), day(2)), 0);
+    }
+
+    #[test]
+    fn test_compact_nothing() {
+        let events = vec![BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        }];
+
+        assert_eq!(compact(&events, 0), Ok(events));
+    }
+
+    #[test]
+    fn test_compact_refuses_prefix_with_other_state() {
+        let events = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::InterestRateChanged { annual_rate: 0.05 },
+            BankAccountEvent::InterestAccrued {
+                amount: 10.0,
+                balance: 1_010.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 960.0,
+            },
+        ];
+
+        // Interest postings are keyed by sequence, which dropping the deposit would shift.
+        assert_eq!(
+            compact(&events, 3),
+            Err(CompactionError {
+                field: "interest_postings".to_string()
+            })
+        );
+        assert_eq!(compact(&events, 2).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_compact_refuses_tail_keyed_by_sequence() {
+        let events = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 950.0,
+            },
+            BankAccountEvent::InterestAccrued {
+                amount: 95.0,
+                balance: 1_045.0,
+            },
+        ];
+        let correct_interest = || BankAccountCommand::CorrectInterest {
+            original_sequence: 3,
+            corrected_amount: 9.5,
+            reason: "rate entered as 100% instead of 10%".to_string(),
+        };
+
+        assert_eq!(
+            compact(&events, 2),
+            Err(CompactionError {
+                field: "interest_postings".to_string()
+            })
+        );
+
+        // The full stream corrects the posting at sequence 3; folding the first two events
+        // into one checkpoint would move it to sequence 2.
+        TestFramework::<BankAccount>::with(BankAccountServices::default())
+            .given(events.clone())
+            .when(correct_interest())
+            .then_expect_events(vec![BankAccountEvent::InterestCorrected {
+                original_sequence: 3,
+                original_amount: 95.0,
+                corrected_amount: 9.5,
+                reason: "rate entered as 100% instead of 10%".to_string(),
+                balance: 959.5,
+            }]);
+        let mut checkpointed = vec![BankAccountEvent::BalanceEstablished { balance: 950.0 }];
+        checkpointed.push(events[2].clone());
+        TestFramework::<BankAccount>::with(BankAccountServices::default())
+            .given(checkpointed)
+            .when(correct_interest())
+            .then_expect_error(BankAccountError("interest posting not found".to_string()));
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// A calendar date (proleptic Gregorian, UTC), serialized as `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// The UTC date containing `time`.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as i64,
+            Err(before) => {
+                let before = before.duration();
+                -(before.as_secs() as i64) - i64::from(before.subsec_nanos() > 0)
+            }
+        };
+        Self::from_days_since_epoch(seconds.div_euclid(SECONDS_PER_DAY as i64))
+    }
+
+    /// Midnight UTC at the start of this date.
+    pub fn start_of_day(&self) -> SystemTime {
+        let days = self.days_since_epoch();
+        let offset = Duration::from_secs(days.unsigned_abs() * SECONDS_PER_DAY);
+        if days >= 0 {
+            UNIX_EPOCH + offset
+        } else {
+            UNIX_EPOCH - offset
+        }
+    }
+
+    pub fn days_since_epoch(&self) -> i64 {
+        // Howard Hinnant's `days_from_civil`.
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = (i64::from(self.month) + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        // Howard Hinnant's `civil_from_days`.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+
+        Date { year, month, day }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseDateError(String);
+
+impl Display for ParseDateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid date: '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDateError(s.to_string());
+        let mut parts = s.splitn(3, '-');
+        let year = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        let month = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        let day = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+
+        Date::from_ymd(year, month, day).ok_or_else(err)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_days_since_epoch() {
+        assert_eq!(
+            Date::from_days_since_epoch(0),
+            Date::from_ymd(1970, 1, 1).unwrap()
+        );
+        assert_eq!(
+            Date::from_days_since_epoch(11_016),
+            Date::from_ymd(2000, 2, 29).unwrap()
+        );
+        assert_eq!(
+            Date::from_days_since_epoch(-1),
+            Date::from_ymd(1969, 12, 31).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_days_since_epoch_round_trip() {
+        for days in [-719_468, -1, 0, 11_016, 19_508, 2_932_896] {
+            assert_eq!(Date::from_days_since_epoch(days).days_since_epoch(), days);
+        }
+    }
+
+    #[test]
+    fn test_system_time_conversion() {
+        let date = Date::from_ymd(2023, 5, 31).unwrap();
+        let noon = date.start_of_day() + Duration::from_secs(12 * 3_600);
+
+        assert_eq!(Date::from_system_time(date.start_of_day()), date);
+        assert_eq!(Date::from_system_time(noon), date);
+        assert_eq!(
+            Date::from_system_time(UNIX_EPOCH - Duration::from_secs(1)),
+            Date::from_ymd(1969, 12, 31).unwrap()
+        );
+        assert_eq!(
+            Date::from_system_time(UNIX_EPOCH - Duration::from_secs(86_400)),
+            Date::from_ymd(1969, 12, 31).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let date: Date = "2023-05-09".parse().unwrap();
+
+        assert_eq!(date, Date::from_ymd(2023, 5, 9).unwrap());
+        assert_eq!(date.to_string(), "2023-05-09");
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2023-05".parse::<Date>().is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use cqrs_es::{Aggregate, AggregateContext, AggregateError, EventEnvelope, EventStore};
+
+/// Wraps an `EventStore` and refuses to commit an event that is identical to the one
+/// immediately preceding it for the same aggregate.
+pub struct DedupStore<ES> {
+    inner: ES,
+}
+
+impl<ES> DedupStore<ES> {
+    pub fn new(inner: ES) -> Self {
+        Self { inner }
+    }
+}
+
+pub struct DedupStoreAggregateContext<A, AC>
+where
+    A: Aggregate,
+{
+    aggregate_id: String,
+    last_event: Option<A::Event>,
+    inner: AC,
+}
+
+impl<A, AC> AggregateContext<A> for DedupStoreAggregateContext<A, AC>
+where
+    A: Aggregate,
+    AC: AggregateContext<A> + Send,
+{
+    fn aggregate(&self) -> &A {
+        self.inner.aggregate()
+    }
+}
+
+#[async_trait]
+impl<A, ES> EventStore<A> for DedupStore<ES>
+where
+    A: Aggregate + 'static,
+    ES: EventStore<A>,
+    ES::AC: Send + 'static,
+{
+    type AC = DedupStoreAggregateContext<A, ES::AC>;
+
+    async fn load_events(
+        &self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventEnvelope<A>>, AggregateError<A::Error>> {
+        self.inner.load_events(aggregate_id).await
+    }
+
+    async fn load_aggregate(
+        &self,
+        aggregate_id: &str,
+    ) -> Result<Self::AC, AggregateError<A::Error>> {
+        let last_event = self
+            .inner
+            .load_events(aggregate_id)
+            .await?
+            .pop()
+            .map(|envelope| envelope.payload);
+        let inner = self.inner.load_aggregate(aggregate_id).await?;
+
+        Ok(DedupStoreAggregateContext {
+            aggregate_id: aggregate_id.to_string(),
+            last_event,
+            inner,
+        })
+    }
+
+    async fn commit(
+        &self,
+        events: Vec<A::Event>,
+        context: Self::AC,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventEnvelope<A>>, AggregateError<A::Error>> {
+        let mut previous = context.last_event.as_ref();
+        for event in &events {
+            if previous == Some(event) {
+                return Err(AggregateError::UnexpectedError(
+                    format!(
+                        "duplicate event rejected for aggregate ID '{}'",
+                        context.aggregate_id
+                    )
+                    .into(),
+                ));
+            }
+            previous = Some(event);
+        }
+
+        self.inner.commit(events, context.inner, metadata).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BankAccount, BankAccountEvent};
+    use cqrs_es::mem_store::MemStore;
+
+    fn deposit() -> BankAccountEvent {
+        BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_duplicate_of_previous_commit() {
+        let store = DedupStore::new(MemStore::<BankAccount>::default());
+
+        let context = store.load_aggregate("acct-1").await.unwrap();
+        store
+            .commit(vec![deposit()], context, HashMap::new())
+            .await
+            .unwrap();
+
+        let context = store.load_aggregate("acct-1").await.unwrap();
+        let result = store.commit(vec![deposit()], context, HashMap::new()).await;
+
+        assert!(matches!(result, Err(AggregateError::UnexpectedError(_))));
+        assert_eq!(store.load_events("acct-1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_duplicate_within_batch() {
+        let store = DedupStore::new(MemStore::<BankAccount>::default());
+
+        let context = store.load_aggregate("acct-1").await.unwrap();
+        let result = store
+            .commit(vec![deposit(), deposit()], context, HashMap::new())
+            .await;
+
+        assert!(result.is_err());
+        assert!(store.load_events("acct-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_allows_same_event_for_other_aggregate() {
+        let store = DedupStore::new(MemStore::<BankAccount>::default());
+
+        let context = store.load_aggregate("acct-1").await.unwrap();
+        store
+            .commit(vec![deposit()], context, HashMap::new())
+            .await
+            .unwrap();
+
+        let context = store.load_aggregate("acct-2").await.unwrap();
+        let result = store.commit(vec![deposit()], context, HashMap::new()).await;
+
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,58 @@
+use serde_json::Value;
+
+use crate::BankAccount;
+
+/// Field-level differences between two account states, one line per differing field.
+///
+/// Fields are compared through their serialized form so every field of `BankAccount` is
+/// covered, including collections such as holds and budgets.
+pub fn diff(a: &BankAccount, b: &BankAccount) -> Vec<String> {
+    let (a, b) = match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(Value::Object(a)), Ok(Value::Object(b))) => (a, b),
+        // A `BankAccount` always serializes to an object.
+        _ => unreachable!(),
+    };
+    a.iter()
+        .filter(|(field, value)| b.get(*field) != Some(value))
+        .map(|(field, value)| {
+            let other = b.get(field).unwrap_or(&Value::Null);
+            format!("{}: {} != {}", field, value, other)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::Date;
+    use crate::BankAccountEvent;
+    use cqrs_es::Aggregate;
+
+    #[test]
+    fn test_diff_reports_balance_and_holds() {
+        let mut a = BankAccount::default();
+        a.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        });
+        let mut b = BankAccount::default();
+        b.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 150.0,
+            balance: 150.0,
+        });
+        b.apply(BankAccountEvent::HoldPlaced {
+            hold_id: "hold-1".to_string(),
+            amount: 50.0,
+            placed_at: Date::from_ymd(2023, 5, 31).unwrap().start_of_day(),
+        });
+
+        let differences = diff(&a, &b);
+
+        assert!(differences.contains(&"balance: 200.0 != 150.0".to_string()));
+        assert!(differences.contains(&r#"holds: {} != {"hold-1":50.0}"#.to_string()));
+        assert!(differences
+            .iter()
+            .any(|line| line.starts_with("event_count:")));
+        assert!(diff(&a, &a).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use cqrs_es::{EventEnvelope, Query};
+
+use crate::date::Date;
+use crate::queries::{effective_date, LedgerEntry};
+use crate::{BankAccount, BankAccountEvent};
+
+/// Each transaction's effective date and signed amount, by account and sequence.
+type Transactions = BTreeMap<(String, usize), (Date, f64)>;
+
+/// Every ledger transaction by account and sequence, with its amount and the date it took
+/// effect, so `VoidDay` can find all the transactions posted on the voided day. Clones share
+/// the same ledger, letting one copy be registered with the framework while another sits in
+/// `BankAccountServices`.
+#[derive(Clone, Default)]
+pub struct LedgerQuery {
+    transactions: Arc<Mutex<Transactions>>,
+}
+
+impl LedgerQuery {
+    /// Converts every amount recorded for `account_id` at `ratio`, as the account's own
+    /// balances are on redenomination.
+    fn convert(&self, account_id: &str, ratio: f64) {
+        let mut transactions = self.transactions.lock().unwrap();
+        for (_, amount) in transactions
+            .range_mut((account_id.to_string(), 0)..=(account_id.to_string(), usize::MAX))
+            .map(|(_, transaction)| transaction)
+        {
+            *amount *= ratio;
+        }
+    }
+
+    pub fn record(&self, account_id: &str, sequence: usize, date: Date, amount: f64) {
+        let mut transactions = self.transactions.lock().unwrap();
+        transactions.insert((account_id.to_string(), sequence), (date, amount));
+    }
+
+    /// The sequence and signed amount of each transaction that took effect on `date`, in
+    /// stream order.
+    pub fn transactions_on(&self, account_id: &str, date: Date) -> Vec<(usize, f64)> {
+        let transactions = self.transactions.lock().unwrap();
+        transactions
+            .range((account_id.to_string(), 0)..=(account_id.to_string(), usize::MAX))
+            .filter(|(_, (posted_on, _))| *posted_on == date)
+            .map(|((_, sequence), (_, amount))| (*sequence, *amount))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Query<BankAccount> for LedgerQuery {
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<BankAccount>]) {
+        for event in events {
+            match &event.payload {
+                BankAccountEvent::Redenominated { ratio, .. } => {
+                    self.convert(aggregate_id, *ratio);
+                    continue;
+                }
+                // A reversal undoes a transaction rather than posting one of its own.
+                BankAccountEvent::TransactionReversed { .. } => continue,
+                _ => {}
+            }
+            let Some(entry) = LedgerEntry::from_event(&event.payload) else {
+                continue;
+            };
+            if let Some(date) = effective_date(event) {
+                self.record(aggregate_id, event.sequence, date, entry.amount);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use cqrs_es::mem_store::MemStore;
+    use cqrs_es::CqrsFramework;
+
+    use crate::clock::FixedClock;
+    use crate::queries::TIMESTAMP_METADATA_KEY;
+    use crate::{BankAccountCommand, BankAccountError, BankAccountServices, PositiveAmount};
+
+    fn timestamped(
+        sequence: usize,
+        timestamp: &str,
+        payload: BankAccountEvent,
+    ) -> EventEnvelope<BankAccount> {
+        EventEnvelope {
+            aggregate_id: "acct-1".to_string(),
+            sequence,
+            payload,
+            metadata: HashMap::from([(TIMESTAMP_METADATA_KEY.to_string(), timestamp.to_string())]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_transactions_by_effective_date() {
+        let query = LedgerQuery::default();
+        let events = [
+            timestamped(
+                1,
+                "2023-05-31T09:00:00Z",
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 200.0,
+                    balance: 200.0,
+                },
+            ),
+            timestamped(
+                2,
+                "2023-05-31T10:00:00Z",
+                BankAccountEvent::NicknameChanged {
+                    nickname: "rainy day".to_string(),
+                },
+            ),
+            timestamped(
+                3,
+                "2023-05-31T11:00:00Z",
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 50.0,
+                    balance: 150.0,
+                },
+            ),
+            timestamped(
+                4,
+                "2023-06-02T09:00:00Z",
+                BankAccountEvent::BackdatedTransactionPosted {
+                    amount: -20.0,
+                    effective_date: Date::from_ymd(2023, 6, 1).unwrap(),
+                    reason: "missed fee".to_string(),
+                    balance: 130.0,
+                },
+            ),
+            timestamped(
+                5,
+                "2023-06-02T10:00:00Z",
+                BankAccountEvent::TransactionReversed {
+                    original_sequence: 4,
+                    amount: 20.0,
+                    balance: 150.0,
+                },
+            ),
+        ];
+
+        query.clone().dispatch("acct-1", &events).await;
+
+        let may_31 = Date::from_ymd(2023, 5, 31).unwrap();
+        assert_eq!(
+            query.transactions_on("acct-1", may_31),
+            vec![(1, 200.0), (3, -50.0)]
+        );
+        assert_eq!(
+            query.transactions_on("acct-1", Date::from_ymd(2023, 6, 1).unwrap()),
+            vec![(4, -20.0)]
+        );
+        assert!(query
+            .transactions_on("acct-1", Date::from_ymd(2023, 6, 2).unwrap())
+            .is_empty());
+        assert!(query.transactions_on("acct-2", may_31).is_empty());
+
+        query
+            .dispatch(
+                "acct-1",
+                &[timestamped(
+                    6,
+                    "2023-06-03T09:00:00Z",
+                    BankAccountEvent::Redenominated {
+                        old_currency: Some("USD".to_string()),
+                        new_currency: "EUR".to_string(),
+                        ratio: 0.5,
+                        balance: 75.0,
+                    },
+                )],
+            )
+            .await;
+        assert_eq!(
+            query.transactions_on("acct-1", may_31),
+            vec![(1, 100.0), (3, -25.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_void_day_reads_the_registered_ledger() {
+        let ledger = LedgerQuery::default();
+        let may_31 = Date::from_ymd(2023, 5, 31).unwrap();
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            vec![Box::new(ledger.clone())],
+            BankAccountServices::default()
+                .with_clock(Box::new(FixedClock::on(may_31)))
+                .with_ledger(ledger),
+        );
+        let commands = [
+            BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: None,
+            },
+            BankAccountCommand::VerifyKyc {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountCommand::DepositMoney {
+                amount: PositiveAmount::new(200.0).unwrap(),
+            },
+            BankAccountCommand::VoidDay { date: may_31 },
+        ];
+        for command in commands {
+            let metadata = HashMap::from([(
+                TIMESTAMP_METADATA_KEY.to_string(),
+                "2023-05-31T09:00:00Z".to_string(),
+            )]);
+            cqrs.execute_with_metadata("acct-1", command, metadata)
+                .await
+                .unwrap();
+        }
+
+        // The deposit was reversed, so voiding the day again finds it already undone.
+        let result = cqrs
+            .execute("acct-1", BankAccountCommand::VoidDay { date: may_31 })
+            .await;
+        assert!(matches!(
+            result,
+            Err(cqrs_es::AggregateError::UserError(BankAccountError(reason)))
+                if reason == "transaction already reversed: 3"
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::BankAccountEvent;
+
+/// Why a legacy record could not be imported; `index` is its position in the batch.
+#[derive(Debug, PartialEq)]
+pub struct ImportError {
+    pub index: usize,
+    pub reason: String,
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "record {}: {}", self.index, self.reason)
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// A transaction record as exported by the legacy core.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LegacyRecord {
+    Deposit { amount: f64 },
+    Withdrawal { amount: f64 },
+    Check { number: String, amount: f64 },
+}
+
+/// Maps a legacy core's transaction history, oldest first, into account events.
+///
+/// The balance each event carries is recomputed from the amounts; the whole batch is
+/// rejected at the first record that is malformed or has a non-positive amount.
+pub fn import_events(raw: Vec<Value>) -> Result<Vec<BankAccountEvent>, ImportError> {
+    let mut balance = 0_f64;
+    let mut events = Vec::with_capacity(raw.len());
+    for (index, value) in raw.into_iter().enumerate() {
+        let fail = |reason: String| ImportError { index, reason };
+        let record: LegacyRecord =
+            serde_json::from_value(value).map_err(|err| fail(err.to_string()))?;
+        let amount = match &record {
+            LegacyRecord::Deposit { amount }
+            | LegacyRecord::Withdrawal { amount }
+            | LegacyRecord::Check { amount, .. } => *amount,
+        };
+        if !(amount.is_finite() && amount > 0_f64) {
+            return Err(fail(format!("amount must be positive: {}", amount)));
+        }
+        events.push(match record {
+            LegacyRecord::Deposit { amount } => {
+                balance += amount;
+                BankAccountEvent::CustomerDepositedMoney { amount, balance }
+            }
+            LegacyRecord::Withdrawal { amount } => {
+                balance -= amount;
+                BankAccountEvent::CustomerWithdrewCash { amount, balance }
+            }
+            LegacyRecord::Check { number, amount } => {
+                balance -= amount;
+                BankAccountEvent::CustomerWroteCheck {
+                    check_number: number,
+                    amount,
+                    balance,
+                }
+            }
+        });
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_import_legacy_batch() {
+        let events = import_events(vec![
+            json!({"type": "deposit", "amount": 500.0}),
+            json!({"type": "check", "number": "1170", "amount": 120.0}),
+            json!({"type": "withdrawal", "amount": 80.0}),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+                BankAccountEvent::CustomerWroteCheck {
+                    check_number: "1170".to_string(),
+                    amount: 120.0,
+                    balance: 380.0,
+                },
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 80.0,
+                    balance: 300.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_reports_first_bad_record() {
+        let result = import_events(vec![
+            json!({"type": "deposit", "amount": 500.0}),
+            json!({"type": "check", "amount": 120.0}),
+            json!({"type": "withdrawal", "amount": -80.0}),
+        ]);
+
+        assert_eq!(result.unwrap_err().index, 1);
+
+        let result = import_events(vec![
+            json!({"type": "deposit", "amount": 500.0}),
+            json!({"type": "withdrawal", "amount": -80.0}),
+        ]);
+        assert_eq!(
+            result,
+            Err(ImportError {
+                index: 1,
+                reason: "amount must be positive: -80".to_string(),
+            })
+        );
+    }
+}
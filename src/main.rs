@@ -1,35 +1,694 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use cqrs_es::{Aggregate, DomainEvent};
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Deserialize)]
+pub mod branch_report;
+pub mod broadcast;
+pub mod clock;
+pub mod cloudevent;
+pub mod command_builder;
+pub mod compaction;
+pub mod date;
+pub mod dedup_store;
+pub mod diff;
+pub mod ledger;
+pub mod legacy_import;
+pub mod notification;
+pub mod queries;
+pub mod rate_limit;
+pub mod receipt;
+pub mod redact;
+pub mod replay;
+pub mod request_dedup;
+pub mod snapshot;
+pub mod summary;
+pub mod teller_activity;
+#[cfg(test)]
+pub mod testing;
+pub mod view_repository;
+
+use clock::{Clock, SystemClock};
+use date::Date;
+use ledger::LedgerQuery;
+
+#[derive(Debug, Deserialize, PartialEq)]
 pub enum BankAccountCommand {
-    OpenAccount { account_id: String },
-    DepositMoney { amount: f64 },
-    WithdrawMoney { amount: f64 },
-    WriteCheck { check_number: String, amount: f64 },
+    #[serde(rename_all = "camelCase")]
+    OpenAccount {
+        #[serde(alias = "account_id")]
+        account_id: String,
+        #[serde(alias = "max_holds")]
+        max_holds: Option<usize>,
+        holding_period_days: Option<u32>,
+        branch_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DepositMoney {
+        amount: PositiveAmount,
+    },
+    #[serde(rename_all = "camelCase")]
+    WithdrawMoney {
+        amount: PositiveAmount,
+        category: Option<String>,
+        mcc: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    WriteCheck {
+        #[serde(alias = "check_number")]
+        check_number: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        category: Option<String>,
+        mcc: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetCategoryBudget {
+        category: String,
+        limit: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ScheduleBillPayment {
+        payee: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        due: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    CaptureBillPayment {
+        payee: String,
+        due: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    ApplyBonus {
+        #[serde(alias = "bonus_id")]
+        bonus_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        #[serde(alias = "min_balance")]
+        min_balance: f64,
+    },
+    VoidOpen,
+    #[serde(rename_all = "camelCase")]
+    CloseAccountWithPayout {
+        destination: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReceiveWire {
+        reference: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        sender: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    PlaceHold {
+        #[serde(alias = "hold_id")]
+        hold_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReleaseHold {
+        #[serde(alias = "hold_id")]
+        hold_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    CloseAndTransfer {
+        #[serde(alias = "to_account_id")]
+        to_account_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    AuthorizeCharge {
+        #[serde(alias = "auth_id")]
+        auth_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    CaptureCharge {
+        #[serde(alias = "auth_id")]
+        auth_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    DepositPaycheck {
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        rules: Vec<(String, f64)>,
+    },
+    #[serde(rename_all = "camelCase")]
+    PostBackdated {
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        #[serde(alias = "effective_date")]
+        effective_date: Date,
+        reason: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    AbsorbAccount {
+        #[serde(alias = "from_account_id")]
+        from_account_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetInterestRate {
+        #[serde(alias = "annual_rate")]
+        annual_rate: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    AccrueInterest {
+        days: u32,
+    },
+    #[serde(rename_all = "camelCase")]
+    VerifyKyc {
+        #[serde(alias = "verification_id")]
+        verification_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetNickname {
+        nickname: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetRoundUp {
+        enabled: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    RequestStatement {
+        from: Date,
+        to: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetTransactionLimit {
+        max_transaction: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    DissolveBucket {
+        name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetRejectionAudit {
+        enabled: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    CorrectInterest {
+        original_sequence: usize,
+        corrected_amount: f64,
+        reason: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetExpiration {
+        expires: Date,
+    },
+    CloseAccount,
+    #[serde(rename_all = "camelCase")]
+    HoldDisputedFunds {
+        dispute_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ResolveDispute {
+        dispute_id: String,
+        in_customer_favor: bool,
+    },
+    PauseInterest,
+    ResumeInterest,
+    #[serde(rename_all = "camelCase")]
+    TransferBranch {
+        new_branch_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransferMoney {
+        to_account_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ConfigureSweep {
+        target_balance: f64,
+        sweep_to: String,
+    },
+    RecordBalanceSnapshot,
+    #[serde(rename_all = "camelCase")]
+    VoidDay {
+        date: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    EarnPoints {
+        points: u64,
+        reason: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RedeemPoints {
+        points: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    FlagForReview {
+        reason: String,
+        severity: ReviewSeverity,
+    },
+    ClearReview,
+    #[serde(rename_all = "camelCase")]
+    WithholdTax {
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+        tax_year: i32,
+    },
+    #[serde(rename_all = "camelCase")]
+    Redenominate {
+        new_currency: String,
+        ratio: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    SpinOff {
+        new_account_id: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    TellerDeposit {
+        teller_id: String,
+        amount: PositiveAmount,
+    },
+    #[serde(rename_all = "camelCase")]
+    TellerWithdrawal {
+        teller_id: String,
+        amount: PositiveAmount,
+    },
+    #[serde(rename_all = "camelCase")]
+    PlaceGarnishment {
+        case_number: String,
+        #[serde(deserialize_with = "deserialize_amount")]
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReleaseGarnishment {
+        case_number: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ScheduleRecurringDeposit {
+        schedule_id: String,
+        amount: PositiveAmount,
+        interval_days: u32,
+        next_run: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    CancelRecurringDeposit {
+        schedule_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RunRecurringDeposit {
+        schedule_id: String,
+        due: Date,
+    },
+}
+
+impl BankAccountCommand {
+    pub fn command_type(&self) -> &'static str {
+        match self {
+            BankAccountCommand::OpenAccount { .. } => "OpenAccount",
+            BankAccountCommand::DepositMoney { .. } => "DepositMoney",
+            BankAccountCommand::WithdrawMoney { .. } => "WithdrawMoney",
+            BankAccountCommand::WriteCheck { .. } => "WriteCheck",
+            BankAccountCommand::SetCategoryBudget { .. } => "SetCategoryBudget",
+            BankAccountCommand::ScheduleBillPayment { .. } => "ScheduleBillPayment",
+            BankAccountCommand::CaptureBillPayment { .. } => "CaptureBillPayment",
+            BankAccountCommand::ApplyBonus { .. } => "ApplyBonus",
+            BankAccountCommand::VoidOpen => "VoidOpen",
+            BankAccountCommand::CloseAccountWithPayout { .. } => "CloseAccountWithPayout",
+            BankAccountCommand::ReceiveWire { .. } => "ReceiveWire",
+            BankAccountCommand::PlaceHold { .. } => "PlaceHold",
+            BankAccountCommand::ReleaseHold { .. } => "ReleaseHold",
+            BankAccountCommand::CloseAndTransfer { .. } => "CloseAndTransfer",
+            BankAccountCommand::AuthorizeCharge { .. } => "AuthorizeCharge",
+            BankAccountCommand::CaptureCharge { .. } => "CaptureCharge",
+            BankAccountCommand::DepositPaycheck { .. } => "DepositPaycheck",
+            BankAccountCommand::PostBackdated { .. } => "PostBackdated",
+            BankAccountCommand::AbsorbAccount { .. } => "AbsorbAccount",
+            BankAccountCommand::SetInterestRate { .. } => "SetInterestRate",
+            BankAccountCommand::AccrueInterest { .. } => "AccrueInterest",
+            BankAccountCommand::VerifyKyc { .. } => "VerifyKyc",
+            BankAccountCommand::SetNickname { .. } => "SetNickname",
+            BankAccountCommand::SetRoundUp { .. } => "SetRoundUp",
+            BankAccountCommand::RequestStatement { .. } => "RequestStatement",
+            BankAccountCommand::SetTransactionLimit { .. } => "SetTransactionLimit",
+            BankAccountCommand::DissolveBucket { .. } => "DissolveBucket",
+            BankAccountCommand::SetRejectionAudit { .. } => "SetRejectionAudit",
+            BankAccountCommand::CorrectInterest { .. } => "CorrectInterest",
+            BankAccountCommand::SetExpiration { .. } => "SetExpiration",
+            BankAccountCommand::CloseAccount => "CloseAccount",
+            BankAccountCommand::HoldDisputedFunds { .. } => "HoldDisputedFunds",
+            BankAccountCommand::ResolveDispute { .. } => "ResolveDispute",
+            BankAccountCommand::PauseInterest => "PauseInterest",
+            BankAccountCommand::ResumeInterest => "ResumeInterest",
+            BankAccountCommand::TransferBranch { .. } => "TransferBranch",
+            BankAccountCommand::TransferMoney { .. } => "TransferMoney",
+            BankAccountCommand::ConfigureSweep { .. } => "ConfigureSweep",
+            BankAccountCommand::RecordBalanceSnapshot => "RecordBalanceSnapshot",
+            BankAccountCommand::VoidDay { .. } => "VoidDay",
+            BankAccountCommand::EarnPoints { .. } => "EarnPoints",
+            BankAccountCommand::RedeemPoints { .. } => "RedeemPoints",
+            BankAccountCommand::FlagForReview { .. } => "FlagForReview",
+            BankAccountCommand::ClearReview => "ClearReview",
+            BankAccountCommand::WithholdTax { .. } => "WithholdTax",
+            BankAccountCommand::Redenominate { .. } => "Redenominate",
+            BankAccountCommand::SpinOff { .. } => "SpinOff",
+            BankAccountCommand::TellerDeposit { .. } => "TellerDeposit",
+            BankAccountCommand::TellerWithdrawal { .. } => "TellerWithdrawal",
+            BankAccountCommand::PlaceGarnishment { .. } => "PlaceGarnishment",
+            BankAccountCommand::ReleaseGarnishment { .. } => "ReleaseGarnishment",
+            BankAccountCommand::ScheduleRecurringDeposit { .. } => "ScheduleRecurringDeposit",
+            BankAccountCommand::CancelRecurringDeposit { .. } => "CancelRecurringDeposit",
+            BankAccountCommand::RunRecurringDeposit { .. } => "RunRecurringDeposit",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum BankAccountEvent {
+    #[serde(rename_all = "camelCase")]
     AccountOpened {
+        #[serde(alias = "account_id")]
         account_id: String,
+        #[serde(alias = "max_holds")]
+        max_holds: Option<usize>,
+        first_withdrawal_allowed_after: Option<Date>,
+        branch_id: Option<String>,
     },
+    #[serde(rename_all = "camelCase")]
     CustomerDepositedMoney {
         amount: f64,
         balance: f64,
     },
+    #[serde(rename_all = "camelCase")]
     CustomerWithdrewCash {
         amount: f64,
         balance: f64,
     },
+    #[serde(rename_all = "camelCase")]
     CustomerWroteCheck {
+        #[serde(alias = "check_number")]
+        check_number: String,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    CategoryBudgetSet {
+        category: String,
+        limit: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    CategorySpendRecorded {
+        category: String,
+        amount: f64,
+        date: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    BalanceEstablished {
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    BillPaymentScheduled {
+        payee: String,
+        amount: f64,
+        due: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    BillPaid {
+        payee: String,
+        amount: f64,
+        due: Date,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    BonusApplied {
+        #[serde(alias = "bonus_id")]
+        bonus_id: String,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    CheckPendingValidation {
+        #[serde(alias = "check_number")]
         check_number: String,
+    },
+    AccountOpenVoided,
+    #[serde(rename_all = "camelCase")]
+    AccountClosed {
+        #[serde(alias = "payout_destination")]
+        payout_destination: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    WireReceived {
+        reference: String,
+        amount: f64,
+        sender: String,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    HoldPlaced {
+        #[serde(alias = "hold_id")]
+        hold_id: String,
+        amount: f64,
+        #[serde(alias = "placed_at")]
+        placed_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    HoldReleased {
+        #[serde(alias = "hold_id")]
+        hold_id: String,
+        #[serde(alias = "released_at")]
+        released_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    CustomerTransferredMoney {
+        #[serde(alias = "to_account_id")]
+        to_account_id: String,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ChargeCaptured {
+        #[serde(alias = "auth_id")]
+        auth_id: String,
+        amount: f64,
+        balance: f64,
+        #[serde(alias = "captured_at")]
+        captured_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    PaycheckAllocated {
+        bucket: String,
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    BackdatedTransactionPosted {
+        amount: f64,
+        #[serde(alias = "effective_date")]
+        effective_date: Date,
+        reason: String,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    BalanceAbsorbed {
+        #[serde(alias = "from_account_id")]
+        from_account_id: String,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    InterestRateChanged {
+        #[serde(alias = "annual_rate")]
+        annual_rate: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    InterestAccrued {
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    KycVerified {
+        #[serde(alias = "verification_id")]
+        verification_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    NicknameChanged {
+        nickname: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RoundUpChanged {
+        enabled: bool,
+    },
+    /// A withdrawal's rounding difference set aside in the `ROUND_UP_BUCKET`. The money stays
+    /// in the account, so `balance` is unchanged by the move.
+    #[serde(rename_all = "camelCase")]
+    RoundUpSaved {
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatementRequested {
+        from: Date,
+        to: Date,
+        requested_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransactionLimitSet {
+        max_transaction: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    BucketDissolved {
+        name: String,
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    RejectionAuditChanged {
+        enabled: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    CommandRejected {
+        command_type: String,
+        reason: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    InterestCorrected {
+        original_sequence: usize,
+        original_amount: f64,
+        corrected_amount: f64,
+        reason: String,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ExpirationSet {
+        expires: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    DisputedFundsHeld {
+        dispute_id: String,
+        amount: f64,
+        placed_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    DisputeResolved {
+        dispute_id: String,
+        in_customer_favor: bool,
+        amount: f64,
+        balance: f64,
+        resolved_at: SystemTime,
+    },
+    InterestPaused,
+    InterestResumed,
+    #[serde(rename_all = "camelCase")]
+    MerchantSpendRecorded {
+        mcc: String,
+        amount: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    BranchTransferred {
+        old: Option<String>,
+        new: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SweepConfigured {
+        target_balance: f64,
+        sweep_to: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    BalanceSnapshotted {
+        balance: f64,
+        as_of: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransactionReversed {
+        original_sequence: usize,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    DayVoided {
+        date: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    PointsEarned {
+        points: u64,
+        reason: String,
+        total: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    PointsRedeemed {
+        points: u64,
+        total: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    FlaggedForReview {
+        reason: String,
+        severity: ReviewSeverity,
+    },
+    ReviewCleared,
+    #[serde(rename_all = "camelCase")]
+    TaxWithheld {
+        amount: f64,
+        balance: f64,
+        tax_year: i32,
+    },
+    #[serde(rename_all = "camelCase")]
+    Redenominated {
+        old_currency: Option<String>,
+        new_currency: String,
+        ratio: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    AccountSpunOff {
+        new_account_id: String,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    TellerDeposited {
+        teller_id: String,
         amount: f64,
         balance: f64,
     },
+    #[serde(rename_all = "camelCase")]
+    TellerWithdrew {
+        teller_id: String,
+        amount: f64,
+        balance: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    GarnishmentPlaced {
+        case_number: String,
+        amount: f64,
+        placed_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    GarnishmentReleased {
+        case_number: String,
+        released_at: SystemTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    RecurringDepositScheduled {
+        schedule_id: String,
+        amount: f64,
+        interval_days: u32,
+        next_run: Date,
+    },
+    #[serde(rename_all = "camelCase")]
+    RecurringDepositCancelled {
+        schedule_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RecurringDepositAdvanced {
+        schedule_id: String,
+        next_run: Date,
+    },
 }
 
 impl DomainEvent for BankAccountEvent {
@@ -39,6 +698,60 @@ impl DomainEvent for BankAccountEvent {
             BankAccountEvent::CustomerDepositedMoney { .. } => "CustomerDepositedMoney",
             BankAccountEvent::CustomerWithdrewCash { .. } => "CustomerWithdrewCash",
             BankAccountEvent::CustomerWroteCheck { .. } => "CustomerWroteCheck",
+            BankAccountEvent::CategoryBudgetSet { .. } => "CategoryBudgetSet",
+            BankAccountEvent::CategorySpendRecorded { .. } => "CategorySpendRecorded",
+            BankAccountEvent::BalanceEstablished { .. } => "BalanceEstablished",
+            BankAccountEvent::BillPaymentScheduled { .. } => "BillPaymentScheduled",
+            BankAccountEvent::BillPaid { .. } => "BillPaid",
+            BankAccountEvent::BonusApplied { .. } => "BonusApplied",
+            BankAccountEvent::CheckPendingValidation { .. } => "CheckPendingValidation",
+            BankAccountEvent::AccountOpenVoided => "AccountOpenVoided",
+            BankAccountEvent::AccountClosed { .. } => "AccountClosed",
+            BankAccountEvent::WireReceived { .. } => "WireReceived",
+            BankAccountEvent::HoldPlaced { .. } => "HoldPlaced",
+            BankAccountEvent::HoldReleased { .. } => "HoldReleased",
+            BankAccountEvent::CustomerTransferredMoney { .. } => "CustomerTransferredMoney",
+            BankAccountEvent::ChargeCaptured { .. } => "ChargeCaptured",
+            BankAccountEvent::PaycheckAllocated { .. } => "PaycheckAllocated",
+            BankAccountEvent::BackdatedTransactionPosted { .. } => "BackdatedTransactionPosted",
+            BankAccountEvent::BalanceAbsorbed { .. } => "BalanceAbsorbed",
+            BankAccountEvent::InterestRateChanged { .. } => "InterestRateChanged",
+            BankAccountEvent::InterestAccrued { .. } => "InterestAccrued",
+            BankAccountEvent::KycVerified { .. } => "KycVerified",
+            BankAccountEvent::NicknameChanged { .. } => "NicknameChanged",
+            BankAccountEvent::RoundUpChanged { .. } => "RoundUpChanged",
+            BankAccountEvent::RoundUpSaved { .. } => "RoundUpSaved",
+            BankAccountEvent::StatementRequested { .. } => "StatementRequested",
+            BankAccountEvent::TransactionLimitSet { .. } => "TransactionLimitSet",
+            BankAccountEvent::BucketDissolved { .. } => "BucketDissolved",
+            BankAccountEvent::RejectionAuditChanged { .. } => "RejectionAuditChanged",
+            BankAccountEvent::CommandRejected { .. } => "CommandRejected",
+            BankAccountEvent::InterestCorrected { .. } => "InterestCorrected",
+            BankAccountEvent::ExpirationSet { .. } => "ExpirationSet",
+            BankAccountEvent::DisputedFundsHeld { .. } => "DisputedFundsHeld",
+            BankAccountEvent::DisputeResolved { .. } => "DisputeResolved",
+            BankAccountEvent::InterestPaused => "InterestPaused",
+            BankAccountEvent::InterestResumed => "InterestResumed",
+            BankAccountEvent::MerchantSpendRecorded { .. } => "MerchantSpendRecorded",
+            BankAccountEvent::BranchTransferred { .. } => "BranchTransferred",
+            BankAccountEvent::SweepConfigured { .. } => "SweepConfigured",
+            BankAccountEvent::BalanceSnapshotted { .. } => "BalanceSnapshotted",
+            BankAccountEvent::TransactionReversed { .. } => "TransactionReversed",
+            BankAccountEvent::DayVoided { .. } => "DayVoided",
+            BankAccountEvent::PointsEarned { .. } => "PointsEarned",
+            BankAccountEvent::PointsRedeemed { .. } => "PointsRedeemed",
+            BankAccountEvent::FlaggedForReview { .. } => "FlaggedForReview",
+            BankAccountEvent::ReviewCleared => "ReviewCleared",
+            BankAccountEvent::TaxWithheld { .. } => "TaxWithheld",
+            BankAccountEvent::Redenominated { .. } => "Redenominated",
+            BankAccountEvent::AccountSpunOff { .. } => "AccountSpunOff",
+            BankAccountEvent::TellerDeposited { .. } => "TellerDeposited",
+            BankAccountEvent::TellerWithdrew { .. } => "TellerWithdrew",
+            BankAccountEvent::GarnishmentPlaced { .. } => "GarnishmentPlaced",
+            BankAccountEvent::GarnishmentReleased { .. } => "GarnishmentReleased",
+            BankAccountEvent::RecurringDepositScheduled { .. } => "RecurringDepositScheduled",
+            BankAccountEvent::RecurringDepositCancelled { .. } => "RecurringDepositCancelled",
+            BankAccountEvent::RecurringDepositAdvanced { .. } => "RecurringDepositAdvanced",
         };
 
         event_type.to_string()
@@ -49,6 +762,51 @@ impl DomainEvent for BankAccountEvent {
     }
 }
 
+/// The commands, keyed by target account, that a saga sends when `event` spins funds off
+/// `from_account_id`: open the new account, then absorb the amount into it.
+pub fn spin_off_saga(
+    from_account_id: &str,
+    event: &BankAccountEvent,
+) -> Vec<(String, BankAccountCommand)> {
+    let BankAccountEvent::AccountSpunOff {
+        new_account_id,
+        amount,
+        ..
+    } = event
+    else {
+        return vec![];
+    };
+    vec![
+        (
+            new_account_id.clone(),
+            BankAccountCommand::OpenAccount {
+                account_id: new_account_id.clone(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: None,
+            },
+        ),
+        (
+            new_account_id.clone(),
+            BankAccountCommand::AbsorbAccount {
+                from_account_id: from_account_id.to_string(),
+                amount: *amount,
+            },
+        ),
+    ]
+}
+
+/// The events in `events` whose `event_type()` is `event_type`, in stream order.
+pub fn events_of_type<'a>(
+    events: &'a [BankAccountEvent],
+    event_type: &str,
+) -> Vec<&'a BankAccountEvent> {
+    events
+        .iter()
+        .filter(|event| event.event_type() == event_type)
+        .collect()
+}
+
 #[derive(Debug, PartialEq)]
 pub struct BankAccountError(String);
 
@@ -60,150 +818,6513 @@ impl Display for BankAccountError {
 
 impl std::error::Error for BankAccountError {}
 
+/// A strictly positive, finite amount of money; commands carrying one are rejected at
+/// deserialization time rather than in `handle`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(into = "f64")]
+pub struct PositiveAmount(f64);
+
+impl PositiveAmount {
+    pub fn new(amount: f64) -> Result<Self, BankAccountError> {
+        if !(amount.is_finite() && amount > 0_f64) {
+            return Err(BankAccountError(format!(
+                "amount must be positive: {}",
+                amount
+            )));
+        }
+        Ok(PositiveAmount(amount))
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for PositiveAmount {
+    type Error = BankAccountError;
+
+    fn try_from(amount: f64) -> Result<Self, Self::Error> {
+        PositiveAmount::new(amount)
+    }
+}
+
+impl From<PositiveAmount> for f64 {
+    fn from(amount: PositiveAmount) -> Self {
+        amount.0
+    }
+}
+
+impl<'de> Deserialize<'de> for PositiveAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let amount = deserialize_amount(deserializer)?;
+        PositiveAmount::new(amount).map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a command's `amount` from a JSON integer or float. String-encoded numbers
+/// such as `"200"` are rejected with an error naming the field rather than coerced.
+fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    struct AmountVisitor;
+
+    impl<'de> Visitor<'de> for AmountVisitor {
+        type Value = f64;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "a JSON number for `amount`")
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<f64, E> {
+            Ok(value)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<f64, E> {
+            Err(E::custom(format!(
+                "`amount` must be a JSON number, not the string \"{}\"",
+                value
+            )))
+        }
+    }
+
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+const KYC_REQUIRED: &str = "kyc required";
+const UNDER_REVIEW: &str = "account under review";
+const HOLDING_PERIOD_ACTIVE: &str = "holding period active";
+const FUNDS_NOT_AVAILABLE: &str = "funds not available";
+const FUNDS_GARNISHED: &str = "funds garnished";
+const TRANSACTION_TOO_LARGE: &str = "transaction too large";
+const BUDGET_EXCEEDED: &str = "budget exceeded";
+const TOO_MANY_HOLDS: &str = "too many holds";
+
+/// The errors that refuse a command on authorization grounds or for exceeding a limit; some
+/// are followed by a detail such as the shortfall. Audited accounts record these as
+/// `CommandRejected`; any other error is returned.
+const AUDITED_REJECTIONS: [&str; 8] = [
+    KYC_REQUIRED,
+    UNDER_REVIEW,
+    HOLDING_PERIOD_ACTIVE,
+    FUNDS_NOT_AVAILABLE,
+    FUNDS_GARNISHED,
+    TRANSACTION_TOO_LARGE,
+    BUDGET_EXCEEDED,
+    TOO_MANY_HOLDS,
+];
+
+impl BankAccountError {
+    fn is_audited(&self) -> bool {
+        AUDITED_REJECTIONS
+            .iter()
+            .any(|prefix| self.0.starts_with(prefix))
+    }
+}
+
 impl From<&str> for BankAccountError {
     fn from(value: &str) -> Self {
         BankAccountError(value.to_string())
     }
 }
 
-pub struct BankAccountServices;
+pub struct BankAccountServices {
+    pub services: Box<dyn BankAccountApi>,
+    pub clock: Box<dyn Clock>,
+    pub review_withdrawal_limit: f64,
+    pub ledger: LedgerQuery,
+}
 
 impl BankAccountServices {
-    async fn _atm_withdrawal(&self, _atm_id: &str, _amount: f64) -> Result<(), AtmError> {
+    pub fn new(services: Box<dyn BankAccountApi>) -> Self {
+        Self {
+            services,
+            clock: Box::new(SystemClock),
+            review_withdrawal_limit: REVIEW_WITHDRAWAL_LIMIT,
+            ledger: LedgerQuery::default(),
+        }
+    }
+
+    pub fn with_clock(self, clock: Box<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    pub fn with_review_withdrawal_limit(self, review_withdrawal_limit: f64) -> Self {
+        Self {
+            review_withdrawal_limit,
+            ..self
+        }
+    }
+
+    pub fn with_ledger(self, ledger: LedgerQuery) -> Self {
+        Self { ledger, ..self }
+    }
+}
+
+impl Default for BankAccountServices {
+    fn default() -> Self {
+        Self::new(Box::new(HappyPathBankAccountServices))
+    }
+}
+
+/// External systems consulted while handling commands.
+#[async_trait]
+pub trait BankAccountApi: Sync + Send {
+    async fn atm_withdrawal(&self, atm_id: &str, amount: f64) -> Result<(), AtmError>;
+    async fn validate_check(&self, account: &str, check: &str) -> Result<(), CheckingError>;
+}
+
+pub struct HappyPathBankAccountServices;
+
+#[async_trait]
+impl BankAccountApi for HappyPathBankAccountServices {
+    async fn atm_withdrawal(&self, _atm_id: &str, _amount: f64) -> Result<(), AtmError> {
         Ok(())
     }
 
-    async fn _validate_check(&self, _account: &str, _check: &str) -> Result<(), CheckingError> {
+    async fn validate_check(&self, _account: &str, _check: &str) -> Result<(), CheckingError> {
         Ok(())
     }
 }
 
 pub struct AtmError;
-pub struct CheckingError;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CheckingError {
+    /// The check-validation service could not be reached.
+    Unavailable,
+    /// The check was examined and rejected.
+    Invalid,
+}
+
+/// Default cap on concurrently placed holds, unless overridden when the account is opened.
+pub const MAX_HOLDS: usize = 25;
+
+/// Shape version of serialized `BankAccount` snapshots; see `snapshot::migrate_snapshot`.
+pub const CURRENT_SCHEMA_VERSION: u16 = 1;
+
+/// The highest annual interest rate an account may be set to, as a fraction (1.0 is 100%).
+pub const MAX_INTEREST_RATE: f64 = 1.0;
+
+/// How far below zero the available balance may fall through floating-point rounding and
+/// still count as covering a debit, e.g. withdrawing 0.8 from a balance of 0.7 + 0.1.
+pub const FUNDS_EPSILON: f64 = 1e-9;
+
+/// The bucket that round-up savings are allocated to.
+pub const ROUND_UP_BUCKET: &str = "savings";
+
+/// How long a released hold stays in the hold history; `average_hold` is exact for windows
+/// up to this long.
+pub const HOLD_HISTORY_RETENTION: Duration = Duration::from_secs(90 * 24 * 3_600);
+
+/// The longest nickname, in characters, a customer may give an account.
+pub const MAX_NICKNAME_LENGTH: usize = 50;
+
+/// Default largest withdrawal allowed while an account is flagged for review.
+pub const REVIEW_WITHDRAWAL_LIMIT: f64 = 1_000.0;
+
+// Fields added within a schema version take their defaults when an older snapshot of that
+// version lacks them; only a change to an existing field's shape needs a new version.
 #[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct BankAccount {
+    schema_version: SchemaVersion,
+    account_id: String,
     opened: bool,
+    closed: bool,
+    event_count: usize,
     balance: f64,
+    budgets: BTreeMap<String, CategoryBudget>,
+    scheduled_payments: Vec<ScheduledPayment>,
+    applied_bonuses: BTreeSet<String>,
+    received_wires: BTreeSet<String>,
+    holds: BTreeMap<String, f64>,
+    hold_history: Vec<HoldPeriod>,
+    max_holds: Option<usize>,
+    first_withdrawal_allowed_after: Option<Date>,
+    branch_id: Option<String>,
+    buckets: BTreeMap<String, f64>,
+    absorbed_accounts: BTreeSet<String>,
+    interest_rate: Option<f64>,
+    kyc_verified: bool,
+    kyc_required: bool,
+    nickname: Option<String>,
+    round_up_enabled: bool,
+    max_transaction: f64,
+    issued_checks: BTreeSet<String>,
+    audit_rejections: bool,
+    interest_postings: BTreeMap<usize, f64>,
+    corrected_interest: BTreeSet<usize>,
+    expires: Option<Date>,
+    disputes: BTreeSet<String>,
+    interest_paused: bool,
+    sweep: Option<SweepConfig>,
+    reversed_transactions: BTreeSet<usize>,
+    points: u64,
+    review: Option<Review>,
+    tax_withheld: BTreeMap<i32, f64>,
+    currency: Option<String>,
+    garnishments: BTreeMap<String, f64>,
+    recurring_deposits: BTreeMap<String, RecurringDeposit>,
 }
 
-#[async_trait]
-impl Aggregate for BankAccount {
-    type Command = BankAccountCommand;
-    type Event = BankAccountEvent;
-    type Error = BankAccountError;
-    type Services = BankAccountServices;
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct SchemaVersion(pub u16);
 
-    fn aggregate_type() -> String {
-        "Account".to_string()
+impl Default for SchemaVersion {
+    fn default() -> Self {
+        SchemaVersion(CURRENT_SCHEMA_VERSION)
     }
+}
 
-    async fn handle(
-        &self,
-        command: Self::Command,
-        _services: &Self::Services,
-    ) -> Result<Vec<Self::Event>, Self::Error> {
-        match command {
-            BankAccountCommand::DepositMoney { amount } => {
-                let balance = self.balance + amount;
-                Ok(vec![BankAccountEvent::CustomerDepositedMoney {
-                    amount,
+/// Monthly spending cap for a category, with the amount spent in the month last recorded.
+#[derive(Default, Deserialize, Serialize)]
+pub struct CategoryBudget {
+    limit: f64,
+    spent: f64,
+    month: (i32, u32),
+}
+
+/// How urgently a flagged account needs manual review.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ReviewSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+/// Why an account was flagged for review.
+#[derive(Deserialize, Serialize)]
+pub struct Review {
+    reason: String,
+    severity: ReviewSeverity,
+}
+
+/// Where cash above `target_balance` is swept to.
+#[derive(Deserialize, Serialize)]
+pub struct SweepConfig {
+    target_balance: f64,
+    sweep_to: String,
+}
+
+/// When a hold was in force; `released_at` is `None` while it is still active.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct HoldPeriod {
+    hold_id: String,
+    amount: f64,
+    placed_at: SystemTime,
+    released_at: Option<SystemTime>,
+}
+
+/// A bill payment whose amount is held against the balance until it is captured.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ScheduledPayment {
+    payee: String,
+    amount: f64,
+    due: Date,
+}
+
+/// An automatic deposit of `amount` every `interval_days`, next made on `next_run`.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct RecurringDeposit {
+    amount: f64,
+    interval_days: u32,
+    next_run: Date,
+}
+
+impl BankAccount {
+    pub fn nickname(&self) -> Option<&str> {
+        self.nickname.as_deref()
+    }
+
+    /// Rewards points, tracked separately from the cash balance.
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    /// The reason the account is under review, if it has been flagged.
+    pub fn review_reason(&self) -> Option<&str> {
+        self.review.as_ref().map(|review| review.reason.as_str())
+    }
+
+    /// Tax withheld in `tax_year`, for year-end (1099-INT) reporting.
+    pub fn tax_withheld_for(&self, tax_year: i32) -> f64 {
+        self.tax_withheld.get(&tax_year).copied().unwrap_or(0.0)
+    }
+
+    /// The posted balance, before any holds are taken into account.
+    pub fn ledger_balance(&self) -> f64 {
+        self.balance
+    }
+
+    /// The ledger balance less any funds held, including those reserved for scheduled payments
+    /// and garnished by court order.
+    pub fn available_balance(&self) -> f64 {
+        let scheduled: f64 = self
+            .scheduled_payments
+            .iter()
+            .map(|payment| payment.amount)
+            .sum();
+        let held: f64 = self.holds.values().sum();
+        self.balance - self.garnished() - scheduled - held
+    }
+
+    /// Funds frozen by garnishment orders.
+    pub fn garnished(&self) -> f64 {
+        self.garnishments.values().sum()
+    }
+
+    /// Time-weighted average of the total amount held over the `window` ending at `now`.
+    /// Holds released more than `HOLD_HISTORY_RETENTION` before the latest release are no
+    /// longer counted.
+    pub fn average_hold(&self, window: Duration, now: SystemTime) -> f64 {
+        if window.is_zero() {
+            return 0_f64;
+        }
+        let start = now.checked_sub(window).unwrap_or(now);
+        let weighted: f64 = self
+            .hold_history
+            .iter()
+            .map(|period| {
+                let from = period.placed_at.max(start);
+                let to = period.released_at.unwrap_or(now).min(now);
+                let held = to.duration_since(from).unwrap_or_default();
+                period.amount * held.as_secs_f64()
+            })
+            .sum();
+        weighted / window.as_secs_f64()
+    }
+
+    /// The portion of deposited paychecks allocated to `bucket`.
+    pub fn bucket_balance(&self, bucket: &str) -> f64 {
+        self.buckets.get(bucket).copied().unwrap_or_default()
+    }
+
+    /// The balance not allocated to any bucket.
+    pub fn unallocated_balance(&self) -> f64 {
+        self.balance - self.buckets.values().sum::<f64>()
+    }
+
+    fn has_funds_for(&self, amount: f64) -> bool {
+        self.available_balance() - amount > -FUNDS_EPSILON
+    }
+
+    /// How much must be deposited before a withdrawal of `amount` can succeed; zero when
+    /// the available balance already covers it.
+    pub fn shortfall_for(&self, amount: f64) -> f64 {
+        (amount - self.available_balance()).max(0_f64)
+    }
+
+    fn ensure_holding_period_over(
+        &self,
+        services: &BankAccountServices,
+    ) -> Result<(), BankAccountError> {
+        if self
+            .first_withdrawal_allowed_after
+            .is_some_and(|allowed| services.clock.today() < allowed)
+        {
+            return Err(HOLDING_PERIOD_ACTIVE.into());
+        }
+        Ok(())
+    }
+
+    /// While the account is under review, rejects withdrawals above the services' limit.
+    fn ensure_review_allows(
+        &self,
+        amount: f64,
+        services: &BankAccountServices,
+    ) -> Result<(), BankAccountError> {
+        if self.review.is_some() && amount > services.review_withdrawal_limit {
+            return Err(UNDER_REVIEW.into());
+        }
+        Ok(())
+    }
+
+    /// Rejects a customer withdrawal of `amount` with the shortfall to deposit.
+    fn ensure_funds_for(&self, amount: f64) -> Result<(), BankAccountError> {
+        if !self.has_funds_for(amount) {
+            return Err(BankAccountError(format!(
+                "{}, shortfall: {}",
+                FUNDS_NOT_AVAILABLE,
+                self.shortfall_for(amount)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rejects settling `amount` against held funds when it would spend money a garnishment
+    /// has since frozen; garnishments take priority over the holds they overlap.
+    fn ensure_not_garnished(&self, amount: f64) -> Result<(), BankAccountError> {
+        if self.balance - amount - self.garnished() < -FUNDS_EPSILON {
+            return Err(FUNDS_GARNISHED.into());
+        }
+        Ok(())
+    }
+
+    fn release_hold(&mut self, hold_id: &str, released_at: SystemTime) {
+        self.holds.remove(hold_id);
+        if let Some(period) = self
+            .hold_history
+            .iter_mut()
+            .rev()
+            .find(|period| period.hold_id == hold_id && period.released_at.is_none())
+        {
+            period.released_at = Some(released_at);
+        }
+        let oldest = released_at
+            .checked_sub(HOLD_HISTORY_RETENTION)
+            .unwrap_or(released_at);
+        self.hold_history
+            .retain(|period| period.released_at.is_none_or(|released| released >= oldest));
+    }
+
+    fn ensure_closable(&self) -> Result<(), BankAccountError> {
+        if self.closed {
+            return Err("account already closed".into());
+        }
+        if !self.scheduled_payments.is_empty() {
+            return Err("account has scheduled payments".into());
+        }
+        if !self.holds.is_empty() {
+            return Err("account has active holds".into());
+        }
+        if !self.garnishments.is_empty() {
+            return Err("account has active garnishments".into());
+        }
+        Ok(())
+    }
+
+    /// Rejects a single transaction above the account's limit; a zero limit means unlimited.
+    fn ensure_within_transaction_limit(&self, amount: f64) -> Result<(), BankAccountError> {
+        if self.max_transaction > 0_f64 && amount > self.max_transaction {
+            return Err(BankAccountError(format!(
+                "{}, limit: {}",
+                TRANSACTION_TOO_LARGE, self.max_transaction
+            )));
+        }
+        Ok(())
+    }
+
+    fn max_holds(&self) -> usize {
+        self.max_holds.unwrap_or(MAX_HOLDS)
+    }
+
+    fn place_hold(
+        &self,
+        hold_id: String,
+        amount: f64,
+        placed_at: SystemTime,
+    ) -> Result<Vec<BankAccountEvent>, BankAccountError> {
+        ensure_positive(amount)?;
+        if self.holds.contains_key(&hold_id) {
+            return Err("hold already placed".into());
+        }
+        if self.holds.len() >= self.max_holds() {
+            return Err(TOO_MANY_HOLDS.into());
+        }
+        if !self.has_funds_for(amount) {
+            return Err(FUNDS_NOT_AVAILABLE.into());
+        }
+        Ok(vec![BankAccountEvent::HoldPlaced {
+            hold_id,
+            amount,
+            placed_at,
+        }])
+    }
+
+    /// Whether the balance is zero, allowing for floating-point dust left by rounding.
+    fn is_empty(&self) -> bool {
+        self.balance.abs() < FUNDS_EPSILON
+    }
+
+    pub fn is_expired(&self, now: Date) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// The command closing the account once it has expired and been emptied.
+    pub fn expiration_commands(&self, now: Date) -> Vec<BankAccountCommand> {
+        if self.is_expired(now) && !self.closed && self.is_empty() {
+            vec![BankAccountCommand::CloseAccount]
+        } else {
+            vec![]
+        }
+    }
+
+    /// The transfer moving available cash above the sweep target out of the account, if a
+    /// sweep is configured and the account is still active on `now`.
+    pub fn sweep_commands(&self, now: Date) -> Vec<BankAccountCommand> {
+        let Some(sweep) = &self.sweep else {
+            return vec![];
+        };
+        let excess = self.available_balance() - sweep.target_balance;
+        if self.closed || self.is_expired(now) || excess <= FUNDS_EPSILON {
+            return vec![];
+        }
+        vec![BankAccountCommand::TransferMoney {
+            to_account_id: sweep.sweep_to.clone(),
+            amount: excess,
+        }]
+    }
+
+    /// Run commands for every recurring deposit due on or before `now`. Each run deposits the
+    /// amount and advances the schedule, so a run that is missed is caught up one interval
+    /// per call.
+    pub fn due_recurring_deposits(&self, now: Date) -> Vec<BankAccountCommand> {
+        self.recurring_deposits
+            .iter()
+            .filter(|(_, schedule)| schedule.next_run <= now)
+            .map(
+                |(schedule_id, schedule)| BankAccountCommand::RunRecurringDeposit {
+                    schedule_id: schedule_id.clone(),
+                    due: schedule.next_run,
+                },
+            )
+            .collect()
+    }
+
+    /// Capture commands for every scheduled payment due on or before `now`.
+    pub fn due_payments(&self, now: Date) -> Vec<BankAccountCommand> {
+        self.scheduled_payments
+            .iter()
+            .filter(|payment| payment.due <= now)
+            .map(|payment| BankAccountCommand::CaptureBillPayment {
+                payee: payment.payee.clone(),
+                due: payment.due,
+            })
+            .collect()
+    }
+
+    fn scheduled_payment(&self, payee: &str, due: Date) -> Option<&ScheduledPayment> {
+        self.scheduled_payments
+            .iter()
+            .find(|payment| payment.payee == payee && payment.due == due)
+    }
+
+    fn record_category_spend(
+        &self,
+        category: String,
+        amount: f64,
+        date: Date,
+    ) -> Result<BankAccountEvent, BankAccountError> {
+        if let Some(budget) = self.budgets.get(&category) {
+            let spent = if budget.month == (date.year(), date.month()) {
+                budget.spent
+            } else {
+                0_f64
+            };
+            if spent + amount > budget.limit {
+                return Err(BankAccountError(format!(
+                    "{} for category: {}",
+                    BUDGET_EXCEEDED, category
+                )));
+            }
+        }
+        Ok(BankAccountEvent::CategorySpendRecorded {
+            category,
+            amount,
+            date,
+        })
+    }
+}
+
+impl BankAccount {
+    async fn handle_command(
+        &self,
+        command: BankAccountCommand,
+        services: &BankAccountServices,
+    ) -> Result<Vec<BankAccountEvent>, BankAccountError> {
+        let closing = matches!(
+            command,
+            BankAccountCommand::CloseAccount
+                | BankAccountCommand::CloseAccountWithPayout { .. }
+                | BankAccountCommand::CloseAndTransfer { .. }
+        );
+        if self.closed && !closing {
+            return Err("account closed".into());
+        }
+        let moves_money = matches!(
+            command,
+            BankAccountCommand::DepositMoney { .. }
+                | BankAccountCommand::WithdrawMoney { .. }
+                | BankAccountCommand::WriteCheck { .. }
+                | BankAccountCommand::ReceiveWire { .. }
+                | BankAccountCommand::DepositPaycheck { .. }
+                | BankAccountCommand::TransferMoney { .. }
+                | BankAccountCommand::SpinOff { .. }
+                | BankAccountCommand::TellerDeposit { .. }
+                | BankAccountCommand::TellerWithdrawal { .. }
+                | BankAccountCommand::RunRecurringDeposit { .. }
+                | BankAccountCommand::CloseAccountWithPayout { .. }
+                | BankAccountCommand::CloseAndTransfer { .. }
+                | BankAccountCommand::ApplyBonus { .. }
+                | BankAccountCommand::AbsorbAccount { .. }
+                | BankAccountCommand::PostBackdated { .. }
+                | BankAccountCommand::ScheduleBillPayment { .. }
+                | BankAccountCommand::CaptureBillPayment { .. }
+                | BankAccountCommand::CaptureCharge { .. }
+                | BankAccountCommand::WithholdTax { .. }
+                | BankAccountCommand::VoidDay { .. }
+                | BankAccountCommand::AccrueInterest { .. }
+                | BankAccountCommand::CorrectInterest { .. }
+                | BankAccountCommand::ResolveDispute { .. }
+                | BankAccountCommand::Redenominate { .. }
+        );
+        // Identity checks apply from account opening, and still apply once the opening is
+        // voided; money can't move until they pass. Streams with no AccountOpened are legacy
+        // accounts from before OpenAccount was recorded, and are exempt: they were never
+        // opened through this flow, so requiring a check now would lock them out.
+        if self.kyc_required && !self.kyc_verified && !self.closed && moves_money {
+            return Err(KYC_REQUIRED.into());
+        }
+
+        match command {
+            BankAccountCommand::OpenAccount {
+                account_id,
+                max_holds,
+                holding_period_days,
+                branch_id,
+            } => {
+                if self.opened {
+                    return Err("account already opened".into());
+                }
+                let first_withdrawal_allowed_after = holding_period_days.map(|days| {
+                    let today = services.clock.today().days_since_epoch();
+                    Date::from_days_since_epoch(today + i64::from(days))
+                });
+                Ok(vec![BankAccountEvent::AccountOpened {
+                    account_id,
+                    max_holds,
+                    first_withdrawal_allowed_after,
+                    branch_id,
+                }])
+            }
+            BankAccountCommand::DepositMoney { amount } => {
+                let amount = amount.value();
+                self.ensure_within_transaction_limit(amount)?;
+                let balance = self.balance + amount;
+                Ok(vec![BankAccountEvent::CustomerDepositedMoney {
+                    amount,
+                    balance,
+                }])
+            }
+            BankAccountCommand::WithdrawMoney {
+                amount,
+                category,
+                mcc,
+            } => {
+                let amount = amount.value();
+                self.ensure_holding_period_over(services)?;
+                self.ensure_within_transaction_limit(amount)?;
+                self.ensure_review_allows(amount, services)?;
+                let merchant_spend = record_merchant_spend(mcc, amount)?;
+                let balance = self.balance - amount;
+                self.ensure_funds_for(amount)?;
+                let mut events = vec![BankAccountEvent::CustomerWithdrewCash { amount, balance }];
+                // The round-up is skipped, and the withdrawal still goes through, when the
+                // unallocated balance left after it cannot cover the difference.
+                if self.round_up_enabled {
+                    // Work in cents so a $4.30 withdrawal saves exactly $0.70.
+                    let cents = (amount * 100_f64).round() as i64;
+                    let round_up = ((100 - cents.rem_euclid(100)) % 100) as f64 / 100_f64;
+                    if round_up > 0_f64
+                        && self.unallocated_balance() - amount - round_up > -FUNDS_EPSILON
+                    {
+                        events.push(BankAccountEvent::RoundUpSaved {
+                            amount: round_up,
+                            balance,
+                        });
+                    }
+                }
+                if let Some(category) = category {
+                    events.push(self.record_category_spend(
+                        category,
+                        amount,
+                        services.clock.today(),
+                    )?);
+                }
+                events.extend(merchant_spend);
+                Ok(events)
+            }
+            BankAccountCommand::WriteCheck {
+                check_number,
+                amount,
+                category,
+                mcc,
+            } => {
+                ensure_positive(amount)?;
+                self.ensure_holding_period_over(services)?;
+                self.ensure_within_transaction_limit(amount)?;
+                self.ensure_review_allows(amount, services)?;
+                let merchant_spend = record_merchant_spend(mcc, amount)?;
+                if self.issued_checks.contains(&check_number) {
+                    return Err("duplicate check number".into());
+                }
+                let balance = self.balance - amount;
+                self.ensure_funds_for(amount)?;
+                let spend = category
+                    .map(|category| {
+                        self.record_category_spend(category, amount, services.clock.today())
+                    })
+                    .transpose()?;
+                let pending_validation = match services
+                    .services
+                    .validate_check(&self.account_id, &check_number)
+                    .await
+                {
+                    Ok(_) => false,
+                    Err(CheckingError::Unavailable) => true,
+                    Err(CheckingError::Invalid) => return Err("check invalid".into()),
+                };
+                let mut events = vec![BankAccountEvent::CustomerWroteCheck {
+                    check_number: check_number.clone(),
+                    amount,
                     balance,
+                }];
+                if pending_validation {
+                    events.push(BankAccountEvent::CheckPendingValidation { check_number });
+                }
+                events.extend(spend);
+                events.extend(merchant_spend);
+                Ok(events)
+            }
+            BankAccountCommand::SetCategoryBudget { category, limit } => {
+                if limit < 0_f64 {
+                    return Err("budget limit must not be negative".into());
+                }
+                Ok(vec![BankAccountEvent::CategoryBudgetSet {
+                    category,
+                    limit,
+                }])
+            }
+            BankAccountCommand::ScheduleBillPayment { payee, amount, due } => {
+                ensure_positive(amount)?;
+                self.ensure_holding_period_over(services)?;
+                if self.scheduled_payment(&payee, due).is_some() {
+                    return Err("payment already scheduled".into());
+                }
+                if !self.has_funds_for(amount) {
+                    return Err(FUNDS_NOT_AVAILABLE.into());
+                }
+                Ok(vec![BankAccountEvent::BillPaymentScheduled {
+                    payee,
+                    amount,
+                    due,
+                }])
+            }
+            BankAccountCommand::CaptureBillPayment { payee, due } => {
+                let amount = match self.scheduled_payment(&payee, due) {
+                    Some(payment) => payment.amount,
+                    None => return Err("no scheduled payment found".into()),
+                };
+                self.ensure_holding_period_over(services)?;
+                self.ensure_not_garnished(amount)?;
+                self.ensure_review_allows(amount, services)?;
+                let balance = self.balance - amount;
+                Ok(vec![BankAccountEvent::BillPaid {
+                    payee,
+                    amount,
+                    due,
+                    balance,
+                }])
+            }
+            BankAccountCommand::ApplyBonus {
+                bonus_id,
+                amount,
+                min_balance,
+            } => {
+                ensure_positive(amount)?;
+                if self.applied_bonuses.contains(&bonus_id) {
+                    return Err("bonus already applied".into());
+                }
+                if self.balance < min_balance {
+                    return Err("bonus condition not met".into());
+                }
+                let balance = self.balance + amount;
+                Ok(vec![BankAccountEvent::BonusApplied {
+                    bonus_id,
+                    amount,
+                    balance,
+                }])
+            }
+            BankAccountCommand::VoidOpen => {
+                if !self.opened {
+                    return Err("account not opened".into());
+                }
+                if self.event_count > 1 || !self.is_empty() {
+                    return Err("account has transactions".into());
+                }
+                Ok(vec![BankAccountEvent::AccountOpenVoided])
+            }
+            BankAccountCommand::CloseAccountWithPayout { destination } => {
+                self.ensure_closable()?;
+                self.ensure_holding_period_over(services)?;
+                self.ensure_review_allows(self.balance, services)?;
+                let mut events = Vec::new();
+                if self.balance > 0_f64 {
+                    events.push(BankAccountEvent::CustomerWithdrewCash {
+                        amount: self.balance,
+                        balance: 0_f64,
+                    });
+                }
+                events.push(BankAccountEvent::AccountClosed {
+                    payout_destination: Some(destination),
+                });
+                Ok(events)
+            }
+            BankAccountCommand::ReceiveWire {
+                reference,
+                amount,
+                sender,
+            } => {
+                ensure_positive(amount)?;
+                if self.received_wires.contains(&reference) {
+                    return Err("duplicate wire".into());
+                }
+                let balance = self.balance + amount;
+                Ok(vec![BankAccountEvent::WireReceived {
+                    reference,
+                    amount,
+                    sender,
+                    balance,
+                }])
+            }
+            BankAccountCommand::PlaceHold { hold_id, amount } => {
+                self.place_hold(hold_id, amount, services.clock.now())
+            }
+            BankAccountCommand::ReleaseHold { hold_id } => {
+                if !self.holds.contains_key(&hold_id) {
+                    return Err("hold not found".into());
+                }
+                Ok(vec![BankAccountEvent::HoldReleased {
+                    hold_id,
+                    released_at: services.clock.now(),
                 }])
             }
-            BankAccountCommand::WithdrawMoney { amount } => {
+            BankAccountCommand::CloseAndTransfer { to_account_id } => {
+                self.ensure_closable()?;
+                self.ensure_holding_period_over(services)?;
+                self.ensure_review_allows(self.balance, services)?;
+                if to_account_id.is_empty() || to_account_id == self.account_id {
+                    return Err("invalid transfer account".into());
+                }
+                let mut events = Vec::new();
+                if self.balance > 0_f64 {
+                    events.push(BankAccountEvent::CustomerTransferredMoney {
+                        to_account_id,
+                        amount: self.balance,
+                        balance: 0_f64,
+                    });
+                }
+                events.push(BankAccountEvent::AccountClosed {
+                    payout_destination: None,
+                });
+                Ok(events)
+            }
+            BankAccountCommand::AuthorizeCharge { auth_id, amount } => {
+                self.ensure_holding_period_over(services)?;
+                self.place_hold(auth_id, amount, services.clock.now())
+            }
+            BankAccountCommand::CaptureCharge { auth_id, amount } => {
+                ensure_positive(amount)?;
+                self.ensure_holding_period_over(services)?;
+                let authorized = match self.holds.get(&auth_id) {
+                    Some(authorized) => *authorized,
+                    None => return Err("authorization not found".into()),
+                };
+                if amount > authorized {
+                    return Err("capture exceeds authorization".into());
+                }
+                self.ensure_not_garnished(amount)?;
+                self.ensure_review_allows(amount, services)?;
                 let balance = self.balance - amount;
-                if balance < 0_f64 {
-                    return Err("funds not available".into());
+                Ok(vec![BankAccountEvent::ChargeCaptured {
+                    auth_id,
+                    amount,
+                    balance,
+                    captured_at: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::DepositPaycheck { amount, rules } => {
+                ensure_positive(amount)?;
+                let total: f64 = rules.iter().map(|(_, percent)| percent).sum();
+                if rules
+                    .iter()
+                    .any(|(_, percent)| !percent.is_finite() || *percent < 0_f64)
+                    || (total - 100_f64).abs() > 1e-9
+                {
+                    return Err("invalid split".into());
+                }
+                let balance = self.balance + amount;
+                let mut events = vec![BankAccountEvent::CustomerDepositedMoney { amount, balance }];
+                events.extend(rules.into_iter().map(|(bucket, percent)| {
+                    BankAccountEvent::PaycheckAllocated {
+                        bucket,
+                        amount: amount * percent / 100_f64,
+                    }
+                }));
+                Ok(events)
+            }
+            BankAccountCommand::PostBackdated {
+                amount,
+                effective_date,
+                reason,
+            } => {
+                // Backdated postings are signed: a negative amount is a debit.
+                ensure_positive(amount.abs())?;
+                if effective_date > services.clock.today() {
+                    return Err("future effective date".into());
+                }
+                if !self.has_funds_for(-amount) {
+                    return Err(FUNDS_NOT_AVAILABLE.into());
+                }
+                let balance = self.balance + amount;
+                Ok(vec![BankAccountEvent::BackdatedTransactionPosted {
+                    amount,
+                    effective_date,
+                    reason,
+                    balance,
+                }])
+            }
+            BankAccountCommand::AbsorbAccount {
+                from_account_id,
+                amount,
+            } => {
+                ensure_positive(amount)?;
+                if self.absorbed_accounts.contains(&from_account_id) {
+                    return Err("account already absorbed".into());
+                }
+                let balance = self.balance + amount;
+                Ok(vec![BankAccountEvent::BalanceAbsorbed {
+                    from_account_id,
+                    amount,
+                    balance,
+                }])
+            }
+            BankAccountCommand::SetInterestRate { annual_rate } => {
+                if annual_rate < 0_f64 {
+                    return Err("interest rate must not be negative".into());
+                }
+                if annual_rate > MAX_INTEREST_RATE {
+                    return Err("rate too high".into());
+                }
+                Ok(vec![BankAccountEvent::InterestRateChanged { annual_rate }])
+            }
+            BankAccountCommand::AccrueInterest { days } => {
+                if self.interest_paused {
+                    return Ok(vec![]);
+                }
+                let annual_rate = match self.interest_rate {
+                    Some(annual_rate) => annual_rate,
+                    None => return Err("account not interest-bearing".into()),
+                };
+                let amount = self.balance * annual_rate * f64::from(days) / 365_f64;
+                let balance = self.balance + amount;
+                Ok(vec![BankAccountEvent::InterestAccrued { amount, balance }])
+            }
+            BankAccountCommand::VerifyKyc { verification_id } => {
+                Ok(vec![BankAccountEvent::KycVerified { verification_id }])
+            }
+            BankAccountCommand::SetNickname { nickname } => {
+                if nickname.chars().count() > MAX_NICKNAME_LENGTH
+                    || nickname.chars().any(char::is_control)
+                {
+                    return Err("invalid nickname".into());
+                }
+                Ok(vec![BankAccountEvent::NicknameChanged { nickname }])
+            }
+            BankAccountCommand::SetRoundUp { enabled } => {
+                Ok(vec![BankAccountEvent::RoundUpChanged { enabled }])
+            }
+            BankAccountCommand::RequestStatement { from, to } => {
+                if from > to {
+                    return Err("invalid date range".into());
+                }
+                Ok(vec![BankAccountEvent::StatementRequested {
+                    from,
+                    to,
+                    requested_at: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::SetTransactionLimit { max_transaction } => {
+                if max_transaction < 0_f64 {
+                    return Err("transaction limit must not be negative".into());
+                }
+                Ok(vec![BankAccountEvent::TransactionLimitSet {
+                    max_transaction,
+                }])
+            }
+            BankAccountCommand::DissolveBucket { name } => {
+                let amount = match self.buckets.get(&name) {
+                    Some(amount) => *amount,
+                    None => return Err("bucket not found".into()),
+                };
+                Ok(vec![BankAccountEvent::BucketDissolved { name, amount }])
+            }
+            BankAccountCommand::SetRejectionAudit { enabled } => {
+                Ok(vec![BankAccountEvent::RejectionAuditChanged { enabled }])
+            }
+            BankAccountCommand::CorrectInterest {
+                original_sequence,
+                corrected_amount,
+                reason,
+            } => {
+                let original_amount = match self.interest_postings.get(&original_sequence) {
+                    Some(amount) => *amount,
+                    None => return Err("interest posting not found".into()),
+                };
+                if self.corrected_interest.contains(&original_sequence) {
+                    return Err("interest already corrected".into());
+                }
+                if !(corrected_amount.is_finite() && corrected_amount >= 0_f64) {
+                    return Err("corrected interest must not be negative".into());
+                }
+                // Correcting interest downwards claws back funds that may have been spent.
+                let clawback = original_amount - corrected_amount;
+                if clawback > 0_f64 {
+                    self.ensure_funds_for(clawback)?;
                 }
-                Ok(vec![BankAccountEvent::CustomerWithdrewCash {
+                let balance = self.balance - original_amount + corrected_amount;
+                Ok(vec![BankAccountEvent::InterestCorrected {
+                    original_sequence,
+                    original_amount,
+                    corrected_amount,
+                    reason,
+                    balance,
+                }])
+            }
+            BankAccountCommand::SetExpiration { expires } => {
+                if expires < services.clock.today() {
+                    return Err("expiration in the past".into());
+                }
+                Ok(vec![BankAccountEvent::ExpirationSet { expires }])
+            }
+            BankAccountCommand::CloseAccount => {
+                self.ensure_closable()?;
+                if !self.is_empty() {
+                    return Err("account has funds".into());
+                }
+                Ok(vec![BankAccountEvent::AccountClosed {
+                    payout_destination: None,
+                }])
+            }
+            BankAccountCommand::HoldDisputedFunds { dispute_id, amount } => {
+                ensure_positive(amount)?;
+                if self.disputes.contains(&dispute_id) || self.holds.contains_key(&dispute_id) {
+                    return Err("dispute already open".into());
+                }
+                // Dispute holds share the holds map, so they count toward the same cap.
+                if self.holds.len() >= self.max_holds() {
+                    return Err(TOO_MANY_HOLDS.into());
+                }
+                if !self.has_funds_for(amount) {
+                    return Err(FUNDS_NOT_AVAILABLE.into());
+                }
+                Ok(vec![BankAccountEvent::DisputedFundsHeld {
+                    dispute_id,
+                    amount,
+                    placed_at: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::ResolveDispute {
+                dispute_id,
+                in_customer_favor,
+            } => {
+                let amount = match self.holds.get(&dispute_id) {
+                    Some(amount) if self.disputes.contains(&dispute_id) => *amount,
+                    _ => return Err("dispute not found".into()),
+                };
+                // Losing the dispute turns the frozen funds into a withdrawal.
+                let balance = if in_customer_favor {
+                    self.balance
+                } else {
+                    self.ensure_not_garnished(amount)?;
+                    self.balance - amount
+                };
+                Ok(vec![BankAccountEvent::DisputeResolved {
+                    dispute_id,
+                    in_customer_favor,
                     amount,
                     balance,
+                    resolved_at: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::PauseInterest => Ok(vec![BankAccountEvent::InterestPaused]),
+            BankAccountCommand::ResumeInterest => Ok(vec![BankAccountEvent::InterestResumed]),
+            BankAccountCommand::TransferBranch { new_branch_id } => {
+                if self.branch_id.as_deref() == Some(new_branch_id.as_str()) {
+                    return Err("account already at branch".into());
+                }
+                Ok(vec![BankAccountEvent::BranchTransferred {
+                    old: self.branch_id.clone(),
+                    new: new_branch_id,
+                }])
+            }
+            BankAccountCommand::TransferMoney {
+                to_account_id,
+                amount,
+            } => {
+                if !amount.is_finite() || amount <= 0_f64 {
+                    return Err("transfer amount must be positive".into());
+                }
+                if to_account_id.is_empty() {
+                    return Err("invalid transfer account".into());
+                }
+                if to_account_id == self.account_id {
+                    return Err("cannot transfer to the same account".into());
+                }
+                self.ensure_holding_period_over(services)?;
+                self.ensure_within_transaction_limit(amount)?;
+                self.ensure_review_allows(amount, services)?;
+                self.ensure_funds_for(amount)?;
+                Ok(vec![BankAccountEvent::CustomerTransferredMoney {
+                    to_account_id,
+                    amount,
+                    balance: self.balance - amount,
+                }])
+            }
+            BankAccountCommand::ConfigureSweep {
+                target_balance,
+                sweep_to,
+            } => {
+                if target_balance < 0_f64 {
+                    return Err("sweep target must not be negative".into());
+                }
+                if sweep_to.is_empty() || sweep_to == self.account_id {
+                    return Err("invalid sweep account".into());
+                }
+                Ok(vec![BankAccountEvent::SweepConfigured {
+                    target_balance,
+                    sweep_to,
+                }])
+            }
+            BankAccountCommand::RecordBalanceSnapshot => {
+                Ok(vec![BankAccountEvent::BalanceSnapshotted {
+                    balance: self.balance,
+                    as_of: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::VoidDay { date } => {
+                let transactions = services.ledger.transactions_on(&self.account_id, date);
+                if transactions.is_empty() {
+                    return Err("no transactions to void".into());
+                }
+                let mut balance = self.balance;
+                let mut deepest_debit = 0_f64;
+                let mut events = Vec::new();
+                for (original_sequence, posted) in transactions.into_iter().rev() {
+                    // The ledger is kept apart from the stream, so it may list a sequence
+                    // this account never reached.
+                    if original_sequence > self.event_count {
+                        return Err(BankAccountError(format!(
+                            "transaction not found: {}",
+                            original_sequence
+                        )));
+                    }
+                    if self.reversed_transactions.contains(&original_sequence) {
+                        return Err(BankAccountError(format!(
+                            "transaction already reversed: {}",
+                            original_sequence
+                        )));
+                    }
+                    balance -= posted;
+                    deepest_debit = deepest_debit.max(self.balance - balance);
+                    events.push(BankAccountEvent::TransactionReversed {
+                        original_sequence,
+                        amount: -posted,
+                        balance,
+                    });
+                }
+                // Reversing a deposit that has since been spent would overdraw the account,
+                // so the funds must cover the lowest point the reversals pass through.
+                self.ensure_funds_for(deepest_debit)?;
+                events.push(BankAccountEvent::DayVoided { date });
+                Ok(events)
+            }
+            BankAccountCommand::EarnPoints { points, reason } => {
+                if points == 0 {
+                    return Err("points must be positive".into());
+                }
+                Ok(vec![BankAccountEvent::PointsEarned {
+                    points,
+                    reason,
+                    total: self.points.saturating_add(points),
+                }])
+            }
+            BankAccountCommand::RedeemPoints { points } => {
+                if points == 0 {
+                    return Err("points must be positive".into());
+                }
+                let total = match self.points.checked_sub(points) {
+                    Some(total) => total,
+                    None => return Err("insufficient points".into()),
+                };
+                Ok(vec![BankAccountEvent::PointsRedeemed { points, total }])
+            }
+            BankAccountCommand::FlagForReview { reason, severity } => {
+                if reason.trim().is_empty() {
+                    return Err("review reason required".into());
+                }
+                Ok(vec![BankAccountEvent::FlaggedForReview {
+                    reason,
+                    severity,
                 }])
             }
-            _ => Ok(vec![]),
+            BankAccountCommand::ClearReview => {
+                if self.review.is_none() {
+                    return Err("account not under review".into());
+                }
+                Ok(vec![BankAccountEvent::ReviewCleared])
+            }
+            BankAccountCommand::WithholdTax { amount, tax_year } => {
+                if amount <= 0_f64 {
+                    return Err("withholding must be positive".into());
+                }
+                if !self.has_funds_for(amount) {
+                    return Err("withholding exceeds balance".into());
+                }
+                Ok(vec![BankAccountEvent::TaxWithheld {
+                    amount,
+                    balance: self.balance - amount,
+                    tax_year,
+                }])
+            }
+            BankAccountCommand::Redenominate {
+                new_currency,
+                ratio,
+            } => {
+                if !(ratio.is_finite() && ratio > 0_f64) {
+                    return Err("redenomination ratio must be positive".into());
+                }
+                if new_currency.is_empty() || self.currency.as_ref() == Some(&new_currency) {
+                    return Err("invalid currency".into());
+                }
+                Ok(vec![BankAccountEvent::Redenominated {
+                    old_currency: self.currency.clone(),
+                    new_currency,
+                    ratio,
+                    balance: self.balance * ratio,
+                }])
+            }
+            BankAccountCommand::SpinOff {
+                new_account_id,
+                amount,
+            } => {
+                if amount <= 0_f64 {
+                    return Err("spin-off amount must be positive".into());
+                }
+                if new_account_id.is_empty() || new_account_id == self.account_id {
+                    return Err("invalid spin-off account".into());
+                }
+                self.ensure_holding_period_over(services)?;
+                self.ensure_within_transaction_limit(amount)?;
+                self.ensure_review_allows(amount, services)?;
+                self.ensure_funds_for(amount)?;
+                Ok(vec![BankAccountEvent::AccountSpunOff {
+                    new_account_id,
+                    amount,
+                    balance: self.balance - amount,
+                }])
+            }
+            BankAccountCommand::TellerDeposit { teller_id, amount } => {
+                let amount = amount.value();
+                self.ensure_within_transaction_limit(amount)?;
+                Ok(vec![BankAccountEvent::TellerDeposited {
+                    teller_id,
+                    amount,
+                    balance: self.balance + amount,
+                }])
+            }
+            BankAccountCommand::TellerWithdrawal { teller_id, amount } => {
+                let amount = amount.value();
+                self.ensure_holding_period_over(services)?;
+                self.ensure_within_transaction_limit(amount)?;
+                self.ensure_review_allows(amount, services)?;
+                self.ensure_funds_for(amount)?;
+                Ok(vec![BankAccountEvent::TellerWithdrew {
+                    teller_id,
+                    amount,
+                    balance: self.balance - amount,
+                }])
+            }
+            BankAccountCommand::PlaceGarnishment {
+                case_number,
+                amount,
+            } => {
+                if amount <= 0_f64 {
+                    return Err("garnishment amount must be positive".into());
+                }
+                if self.garnishments.contains_key(&case_number) {
+                    return Err("garnishment already placed".into());
+                }
+                // Court orders take priority over card and payment holds, so only earlier
+                // garnishments limit how much of the balance can be frozen.
+                if amount > self.balance - self.garnished() + FUNDS_EPSILON {
+                    return Err("garnishment exceeds balance".into());
+                }
+                Ok(vec![BankAccountEvent::GarnishmentPlaced {
+                    case_number,
+                    amount,
+                    placed_at: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::ReleaseGarnishment { case_number } => {
+                if !self.garnishments.contains_key(&case_number) {
+                    return Err("garnishment not found".into());
+                }
+                Ok(vec![BankAccountEvent::GarnishmentReleased {
+                    case_number,
+                    released_at: services.clock.now(),
+                }])
+            }
+            BankAccountCommand::ScheduleRecurringDeposit {
+                schedule_id,
+                amount,
+                interval_days,
+                next_run,
+            } => {
+                if self.recurring_deposits.contains_key(&schedule_id) {
+                    return Err("recurring deposit already scheduled".into());
+                }
+                if interval_days == 0 {
+                    return Err("interval must be at least one day".into());
+                }
+                if next_run < services.clock.today() {
+                    return Err("next run in the past".into());
+                }
+                Ok(vec![BankAccountEvent::RecurringDepositScheduled {
+                    schedule_id,
+                    amount: amount.value(),
+                    interval_days,
+                    next_run,
+                }])
+            }
+            BankAccountCommand::CancelRecurringDeposit { schedule_id } => {
+                if !self.recurring_deposits.contains_key(&schedule_id) {
+                    return Err("recurring deposit not found".into());
+                }
+                Ok(vec![BankAccountEvent::RecurringDepositCancelled {
+                    schedule_id,
+                }])
+            }
+            BankAccountCommand::RunRecurringDeposit { schedule_id, due } => {
+                let schedule = match self.recurring_deposits.get(&schedule_id) {
+                    Some(schedule) if schedule.next_run == due => schedule,
+                    Some(_) => return Err("recurring deposit not due".into()),
+                    None => return Err("recurring deposit not found".into()),
+                };
+                self.ensure_within_transaction_limit(schedule.amount)?;
+                let next_run = Date::from_days_since_epoch(
+                    due.days_since_epoch() + i64::from(schedule.interval_days),
+                );
+                Ok(vec![
+                    BankAccountEvent::CustomerDepositedMoney {
+                        amount: schedule.amount,
+                        balance: self.balance + schedule.amount,
+                    },
+                    BankAccountEvent::RecurringDepositAdvanced {
+                        schedule_id,
+                        next_run,
+                    },
+                ])
+            }
+        }
+    }
+}
+
+/// Rejects a zero, negative or non-finite amount on commands that carry a raw `f64`.
+fn ensure_positive(amount: f64) -> Result<(), BankAccountError> {
+    PositiveAmount::new(amount).map(|_| ())
+}
+
+/// Validates a merchant category code, which must be four digits, and records the spend.
+fn record_merchant_spend(
+    mcc: Option<String>,
+    amount: f64,
+) -> Result<Option<BankAccountEvent>, BankAccountError> {
+    match mcc {
+        None => Ok(None),
+        Some(mcc) if mcc.len() == 4 && mcc.bytes().all(|b| b.is_ascii_digit()) => {
+            Ok(Some(BankAccountEvent::MerchantSpendRecorded {
+                mcc,
+                amount,
+            }))
+        }
+        Some(_) => Err("invalid mcc".into()),
+    }
+}
+
+#[async_trait]
+impl Aggregate for BankAccount {
+    type Command = BankAccountCommand;
+    type Event = BankAccountEvent;
+    type Error = BankAccountError;
+    type Services = BankAccountServices;
+
+    fn aggregate_type() -> String {
+        "Account".to_string()
+    }
+
+    async fn handle(
+        &self,
+        command: Self::Command,
+        services: &Self::Services,
+    ) -> Result<Vec<Self::Event>, Self::Error> {
+        let command_type = command.command_type();
+        match self.handle_command(command, services).await {
+            // Audited accounts record authorization and limit refusals in the stream instead
+            // of returning them.
+            Err(error) if self.audit_rejections && error.is_audited() => {
+                Ok(vec![BankAccountEvent::CommandRejected {
+                    command_type: command_type.to_string(),
+                    reason: error.0,
+                }])
+            }
+            result => result,
+        }
+    }
+
+    fn apply(&mut self, event: Self::Event) {
+        self.event_count += 1;
+        match event {
+            BankAccountEvent::AccountOpened {
+                account_id,
+                max_holds,
+                first_withdrawal_allowed_after,
+                branch_id,
+            } => {
+                self.account_id = account_id;
+                self.max_holds = max_holds;
+                self.first_withdrawal_allowed_after = first_withdrawal_allowed_after;
+                self.branch_id = branch_id;
+                self.opened = true;
+                self.kyc_required = true
+            }
+            BankAccountEvent::CustomerDepositedMoney { amount: _, balance } => {
+                self.balance = balance
+            }
+            BankAccountEvent::CustomerWithdrewCash { amount: _, balance } => self.balance = balance,
+            BankAccountEvent::CustomerWroteCheck {
+                check_number,
+                amount: _,
+                balance,
+            } => {
+                self.issued_checks.insert(check_number);
+                self.balance = balance
+            }
+            BankAccountEvent::CategoryBudgetSet { category, limit } => {
+                self.budgets.entry(category).or_default().limit = limit
+            }
+            BankAccountEvent::CategorySpendRecorded {
+                category,
+                amount,
+                date,
+            } => {
+                if let Some(budget) = self.budgets.get_mut(&category) {
+                    let month = (date.year(), date.month());
+                    if budget.month != month {
+                        budget.month = month;
+                        budget.spent = 0_f64;
+                    }
+                    budget.spent += amount;
+                }
+            }
+            BankAccountEvent::BalanceEstablished { balance } => self.balance = balance,
+            BankAccountEvent::BillPaymentScheduled { payee, amount, due } => self
+                .scheduled_payments
+                .push(ScheduledPayment { payee, amount, due }),
+            BankAccountEvent::BillPaid {
+                payee,
+                amount: _,
+                due,
+                balance,
+            } => {
+                self.scheduled_payments
+                    .retain(|payment| !(payment.payee == payee && payment.due == due));
+                self.balance = balance
+            }
+            BankAccountEvent::BonusApplied {
+                bonus_id,
+                amount: _,
+                balance,
+            } => {
+                self.applied_bonuses.insert(bonus_id);
+                self.balance = balance
+            }
+            BankAccountEvent::CheckPendingValidation { .. } => {}
+            BankAccountEvent::AccountOpenVoided => self.opened = false,
+            BankAccountEvent::AccountClosed { .. } => self.closed = true,
+            BankAccountEvent::WireReceived {
+                reference,
+                amount: _,
+                sender: _,
+                balance,
+            } => {
+                self.received_wires.insert(reference);
+                self.balance = balance
+            }
+            BankAccountEvent::HoldPlaced {
+                hold_id,
+                amount,
+                placed_at,
+            } => {
+                self.holds.insert(hold_id.clone(), amount);
+                self.hold_history.push(HoldPeriod {
+                    hold_id,
+                    amount,
+                    placed_at,
+                    released_at: None,
+                });
+            }
+            BankAccountEvent::HoldReleased {
+                hold_id,
+                released_at,
+            } => self.release_hold(&hold_id, released_at),
+            BankAccountEvent::CustomerTransferredMoney {
+                to_account_id: _,
+                amount: _,
+                balance,
+            } => self.balance = balance,
+            BankAccountEvent::ChargeCaptured {
+                auth_id,
+                amount: _,
+                balance,
+                captured_at,
+            } => {
+                // Capturing settles the authorization; any uncaptured remainder is released.
+                self.release_hold(&auth_id, captured_at);
+                self.balance = balance
+            }
+            BankAccountEvent::PaycheckAllocated { bucket, amount } => {
+                *self.buckets.entry(bucket).or_default() += amount
+            }
+            BankAccountEvent::BackdatedTransactionPosted { balance, .. } => self.balance = balance,
+            BankAccountEvent::BalanceAbsorbed {
+                from_account_id,
+                amount: _,
+                balance,
+            } => {
+                self.absorbed_accounts.insert(from_account_id);
+                self.balance = balance
+            }
+            BankAccountEvent::InterestRateChanged { annual_rate } => {
+                self.interest_rate = Some(annual_rate)
+            }
+            BankAccountEvent::InterestAccrued { amount, balance } => {
+                // `event_count` is this event's sequence number within the account.
+                self.interest_postings.insert(self.event_count, amount);
+                self.balance = balance
+            }
+            BankAccountEvent::KycVerified { .. } => self.kyc_verified = true,
+            BankAccountEvent::NicknameChanged { nickname } => self.nickname = Some(nickname),
+            BankAccountEvent::RoundUpChanged { enabled } => self.round_up_enabled = enabled,
+            BankAccountEvent::RoundUpSaved { amount, balance } => {
+                *self.buckets.entry(ROUND_UP_BUCKET.to_string()).or_default() += amount;
+                self.balance = balance
+            }
+            BankAccountEvent::StatementRequested { .. } => {}
+            BankAccountEvent::TransactionLimitSet { max_transaction } => {
+                self.max_transaction = max_transaction
+            }
+            BankAccountEvent::BucketDissolved { name, amount: _ } => {
+                self.buckets.remove(&name);
+            }
+            BankAccountEvent::RejectionAuditChanged { enabled } => self.audit_rejections = enabled,
+            BankAccountEvent::CommandRejected { .. } => {}
+            BankAccountEvent::InterestCorrected {
+                original_sequence,
+                balance,
+                ..
+            } => {
+                self.corrected_interest.insert(original_sequence);
+                self.balance = balance
+            }
+            BankAccountEvent::ExpirationSet { expires } => self.expires = Some(expires),
+            BankAccountEvent::DisputedFundsHeld {
+                dispute_id,
+                amount,
+                placed_at,
+            } => {
+                self.holds.insert(dispute_id.clone(), amount);
+                self.hold_history.push(HoldPeriod {
+                    hold_id: dispute_id.clone(),
+                    amount,
+                    placed_at,
+                    released_at: None,
+                });
+                self.disputes.insert(dispute_id);
+            }
+            BankAccountEvent::DisputeResolved {
+                dispute_id,
+                balance,
+                resolved_at,
+                ..
+            } => {
+                self.release_hold(&dispute_id, resolved_at);
+                self.disputes.remove(&dispute_id);
+                self.balance = balance
+            }
+            BankAccountEvent::InterestPaused => self.interest_paused = true,
+            BankAccountEvent::InterestResumed => self.interest_paused = false,
+            BankAccountEvent::MerchantSpendRecorded { .. } => {}
+            BankAccountEvent::BranchTransferred { old: _, new } => self.branch_id = Some(new),
+            BankAccountEvent::SweepConfigured {
+                target_balance,
+                sweep_to,
+            } => {
+                self.sweep = Some(SweepConfig {
+                    target_balance,
+                    sweep_to,
+                })
+            }
+            // A checkpoint only: the balance it carries is already the running balance,
+            // but setting it lets a replay start from the snapshot.
+            BankAccountEvent::BalanceSnapshotted { balance, as_of: _ } => self.balance = balance,
+            BankAccountEvent::TransactionReversed {
+                original_sequence,
+                amount: _,
+                balance,
+            } => {
+                self.reversed_transactions.insert(original_sequence);
+                self.balance = balance
+            }
+            BankAccountEvent::DayVoided { .. } => {}
+            BankAccountEvent::PointsEarned { total, .. } => self.points = total,
+            BankAccountEvent::PointsRedeemed { total, .. } => self.points = total,
+            BankAccountEvent::FlaggedForReview { reason, severity } => {
+                self.review = Some(Review { reason, severity })
+            }
+            BankAccountEvent::ReviewCleared => self.review = None,
+            BankAccountEvent::TaxWithheld {
+                amount,
+                balance,
+                tax_year,
+            } => {
+                *self.tax_withheld.entry(tax_year).or_default() += amount;
+                self.balance = balance
+            }
+            BankAccountEvent::Redenominated {
+                old_currency: _,
+                new_currency,
+                ratio,
+                balance,
+            } => {
+                // Everything denominated in the old currency converts at the same ratio.
+                for amount in self
+                    .buckets
+                    .values_mut()
+                    .chain(self.holds.values_mut())
+                    .chain(self.garnishments.values_mut())
+                    .chain(self.interest_postings.values_mut())
+                    .chain(self.tax_withheld.values_mut())
+                    .chain(
+                        self.hold_history
+                            .iter_mut()
+                            .map(|period| &mut period.amount),
+                    )
+                    .chain(
+                        self.scheduled_payments
+                            .iter_mut()
+                            .map(|payment| &mut payment.amount),
+                    )
+                    .chain(
+                        self.recurring_deposits
+                            .values_mut()
+                            .map(|deposit| &mut deposit.amount),
+                    )
+                    .chain(
+                        self.budgets
+                            .values_mut()
+                            .flat_map(|budget| [&mut budget.limit, &mut budget.spent]),
+                    )
+                    .chain(self.sweep.iter_mut().map(|sweep| &mut sweep.target_balance))
+                    .chain([&mut self.max_transaction])
+                {
+                    *amount *= ratio;
+                }
+                self.currency = Some(new_currency);
+                self.balance = balance
+            }
+            BankAccountEvent::AccountSpunOff { balance, .. } => self.balance = balance,
+            BankAccountEvent::TellerDeposited { balance, .. } => self.balance = balance,
+            BankAccountEvent::TellerWithdrew { balance, .. } => self.balance = balance,
+            BankAccountEvent::GarnishmentPlaced {
+                case_number,
+                amount,
+                placed_at: _,
+            } => {
+                self.garnishments.insert(case_number, amount);
+            }
+            BankAccountEvent::GarnishmentReleased {
+                case_number,
+                released_at: _,
+            } => {
+                self.garnishments.remove(&case_number);
+            }
+            BankAccountEvent::RecurringDepositScheduled {
+                schedule_id,
+                amount,
+                interval_days,
+                next_run,
+            } => {
+                self.recurring_deposits.insert(
+                    schedule_id,
+                    RecurringDeposit {
+                        amount,
+                        interval_days,
+                        next_run,
+                    },
+                );
+            }
+            BankAccountEvent::RecurringDepositCancelled { schedule_id } => {
+                self.recurring_deposits.remove(&schedule_id);
+            }
+            BankAccountEvent::RecurringDepositAdvanced {
+                schedule_id,
+                next_run,
+            } => {
+                if let Some(schedule) = self.recurring_deposits.get_mut(&schedule_id) {
+                    schedule.next_run = next_run;
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    println!("Hello, world!");
+}
+
+#[cfg(test)]
+mod aggregate_tests {
+    use super::*;
+    use clock::FixedClock;
+    use cqrs_es::test::TestFramework;
+    use cqrs_es::{EventEnvelope, View};
+    use queries::RecentActivityView;
+    use std::collections::{HashMap, HashSet};
+    use testing::ExpectView;
+
+    type AccountTestFramework = TestFramework<BankAccount>;
+
+    fn positive(amount: f64) -> PositiveAmount {
+        PositiveAmount::new(amount).unwrap()
+    }
+
+    fn may_31() -> SystemTime {
+        Date::from_ymd(2023, 5, 31).unwrap().start_of_day()
+    }
+
+    fn services_on(year: i32, month: u32, day: u32) -> BankAccountServices {
+        let today = Date::from_ymd(year, month, day).unwrap();
+        BankAccountServices::default().with_clock(Box::new(FixedClock::on(today)))
+    }
+
+    #[test]
+    fn test_open_account() {
+        let expected = BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds: None,
+            first_withdrawal_allowed_after: None,
+            branch_id: None,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: None,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        account.apply(expected);
+        assert_eq!(account.account_id, "acct-1");
+        assert!(account.opened);
+    }
+
+    #[test]
+    fn test_open_account_twice() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            }])
+            .when(BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: None,
+            })
+            .then_expect_error(BankAccountError("account already opened".to_string()));
+    }
+
+    #[test]
+    fn test_deposit_money() {
+        let expected = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            })
+            .then_expect_events(vec![expected]);
+    }
+
+    #[test]
+    fn test_deposit_money_with_balance() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 400.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_withdraw_money() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::CustomerWithdrewCash {
+            amount: 100.0,
+            balance: 100.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(100.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_withdraw_money_funds_unavailable() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(200.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 200".to_string(),
+            ))
+    }
+
+    #[test]
+    fn test_set_category_budget() {
+        let expected = BankAccountEvent::CategoryBudgetSet {
+            category: "groceries".to_string(),
+            limit: 100.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetCategoryBudget {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_withdraw_money_within_category_budget() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::CategoryBudgetSet {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+        ];
+        let expected = vec![
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 60.0,
+                balance: 440.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 60.0,
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(60.0),
+                category: Some("groceries".to_string()),
+                mcc: None,
+            })
+            .then_expect_events(expected)
+    }
+
+    #[test]
+    fn test_category_budget_exhausted() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::CategoryBudgetSet {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 60.0,
+                balance: 440.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 60.0,
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous.clone())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(50.0),
+                category: Some("groceries".to_string()),
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError(
+                "budget exceeded for category: groceries".to_string(),
+            ));
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                category: Some("groceries".to_string()),
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError(
+                "budget exceeded for category: groceries".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_uncategorized_spend_ignores_exhausted_budget() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::CategoryBudgetSet {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 100.0,
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+        ];
+        let expected = BankAccountEvent::CustomerWithdrewCash {
+            amount: 50.0,
+            balance: 450.0,
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(50.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_category_budget_resets_monthly() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::CategoryBudgetSet {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 100.0,
+                date: Date::from_ymd(2000, 1, 15).unwrap(),
+            },
+        ];
+        let expected = vec![
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 60.0,
+                balance: 440.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 60.0,
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(60.0),
+                category: Some("groceries".to_string()),
+                mcc: None,
+            })
+            .then_expect_events(expected)
+    }
+
+    #[test]
+    fn test_category_budget_resets_across_month_boundary() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::CategoryBudgetSet {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 100.0,
+                balance: 400.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 100.0,
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+        ];
+        let command = || BankAccountCommand::WithdrawMoney {
+            amount: positive(60.0),
+            category: Some("groceries".to_string()),
+            mcc: None,
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous.clone())
+            .when(command())
+            .then_expect_error(BankAccountError(
+                "budget exceeded for category: groceries".to_string(),
+            ));
+
+        let expected = vec![
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 60.0,
+                balance: 340.0,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "groceries".to_string(),
+                amount: 60.0,
+                date: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 6, 1))
+            .given(previous)
+            .when(command())
+            .then_expect_events(expected)
+    }
+
+    fn bill_scheduled() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::BillPaymentScheduled {
+                payee: "power-co".to_string(),
+                amount: 200.0,
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_schedule_bill_payment() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 500.0,
+            balance: 500.0,
+        };
+        let expected = BankAccountEvent::BillPaymentScheduled {
+            payee: "power-co".to_string(),
+            amount: 200.0,
+            due: Date::from_ymd(2023, 6, 1).unwrap(),
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::ScheduleBillPayment {
+                payee: "power-co".to_string(),
+                amount: 200.0,
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_schedule_bill_payment_funds_unavailable() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(bill_scheduled())
+            .when(BankAccountCommand::ScheduleBillPayment {
+                payee: "water-co".to_string(),
+                amount: 400.0,
+                due: Date::from_ymd(2023, 6, 5).unwrap(),
+            })
+            .then_expect_error(BankAccountError("funds not available".to_string()))
+    }
+
+    #[test]
+    fn test_bill_payment_hold_reduces_available_balance() {
+        let mut account = BankAccount::default();
+        for event in bill_scheduled() {
+            account.apply(event);
+        }
+        assert_eq!(account.balance, 500.0);
+        assert_eq!(account.available_balance(), 300.0);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(bill_scheduled())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(400.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 100".to_string(),
+            ))
+    }
+
+    #[test]
+    fn test_due_payments() {
+        let mut account = BankAccount::default();
+        for event in bill_scheduled() {
+            account.apply(event);
+        }
+
+        assert!(account
+            .due_payments(Date::from_ymd(2023, 5, 31).unwrap())
+            .is_empty());
+        assert_eq!(
+            account.due_payments(Date::from_ymd(2023, 6, 1).unwrap()),
+            vec![BankAccountCommand::CaptureBillPayment {
+                payee: "power-co".to_string(),
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_capture_bill_payment() {
+        let expected = BankAccountEvent::BillPaid {
+            payee: "power-co".to_string(),
+            amount: 200.0,
+            due: Date::from_ymd(2023, 6, 1).unwrap(),
+            balance: 300.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(bill_scheduled())
+            .when(BankAccountCommand::CaptureBillPayment {
+                payee: "power-co".to_string(),
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in bill_scheduled() {
+            account.apply(event);
+        }
+        account.apply(expected);
+        assert_eq!(account.available_balance(), 300.0);
+    }
+
+    #[test]
+    fn test_apply_bonus() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 1000.0,
+            balance: 1000.0,
+        };
+        let expected = BankAccountEvent::BonusApplied {
+            bonus_id: "spring-promo".to_string(),
+            amount: 50.0,
+            balance: 1050.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::ApplyBonus {
+                bonus_id: "spring-promo".to_string(),
+                amount: 50.0,
+                min_balance: 1000.0,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_apply_bonus_condition_not_met() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 999.0,
+            balance: 999.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::ApplyBonus {
+                bonus_id: "spring-promo".to_string(),
+                amount: 50.0,
+                min_balance: 1000.0,
+            })
+            .then_expect_error(BankAccountError("bonus condition not met".to_string()))
+    }
+
+    #[test]
+    fn test_apply_bonus_duplicate() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1000.0,
+                balance: 1000.0,
+            },
+            BankAccountEvent::BonusApplied {
+                bonus_id: "spring-promo".to_string(),
+                amount: 50.0,
+                balance: 1050.0,
+            },
+        ];
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::ApplyBonus {
+                bonus_id: "spring-promo".to_string(),
+                amount: 50.0,
+                min_balance: 1000.0,
+            })
+            .then_expect_error(BankAccountError("bonus already applied".to_string()))
+    }
+
+    struct CheckValidationMock(Result<(), CheckingError>);
+
+    #[async_trait]
+    impl BankAccountApi for CheckValidationMock {
+        async fn atm_withdrawal(&self, _atm_id: &str, _amount: f64) -> Result<(), AtmError> {
+            Ok(())
+        }
+
+        async fn validate_check(&self, _account: &str, _check: &str) -> Result<(), CheckingError> {
+            self.0
+        }
+    }
+
+    fn check_services(response: Result<(), CheckingError>) -> BankAccountServices {
+        BankAccountServices::new(Box::new(CheckValidationMock(response)))
+    }
+
+    #[test]
+    fn test_write_check() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::CustomerWroteCheck {
+            check_number: "1170".to_string(),
+            amount: 50.0,
+            balance: 150.0,
+        };
+
+        AccountTestFramework::with(check_services(Ok(())))
+            .given(vec![previous])
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_write_check_validation_unavailable() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = vec![
+            BankAccountEvent::CustomerWroteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                balance: 150.0,
+            },
+            BankAccountEvent::CheckPendingValidation {
+                check_number: "1170".to_string(),
+            },
+        ];
+
+        AccountTestFramework::with(check_services(Err(CheckingError::Unavailable)))
+            .given(vec![previous])
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(expected)
+    }
+
+    #[test]
+    fn test_write_check_invalid() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+
+        AccountTestFramework::with(check_services(Err(CheckingError::Invalid)))
+            .given(vec![previous])
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError("check invalid".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_replay_does_not_call_services() {
+        let deposit = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let mut account = BankAccount::default();
+        account.apply(deposit.clone());
+
+        // The check service's answer is captured in the emitted events...
+        let services = check_services(Err(CheckingError::Unavailable));
+        let events = account
+            .handle(
+                BankAccountCommand::WriteCheck {
+                    check_number: "1170".to_string(),
+                    amount: 50.0,
+                    category: None,
+                    mcc: None,
+                },
+                &services,
+            )
+            .await
+            .unwrap();
+        for event in events.clone() {
+            account.apply(event);
+        }
+
+        // ...so replaying them rebuilds the same state without any services at all.
+        let mut replayed = BankAccount::default();
+        for event in std::iter::once(deposit).chain(events) {
+            replayed.apply(event);
+        }
+        assert_eq!(
+            serde_json::to_value(&replayed).unwrap(),
+            serde_json::to_value(&account).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_void_open() {
+        let previous = BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds: None,
+            first_withdrawal_allowed_after: None,
+            branch_id: None,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous.clone()])
+            .when(BankAccountCommand::VoidOpen)
+            .then_expect_events(vec![BankAccountEvent::AccountOpenVoided]);
+
+        let mut account = BankAccount::default();
+        account.apply(previous);
+        account.apply(BankAccountEvent::AccountOpenVoided);
+        assert!(!account.opened);
+    }
+
+    #[test]
+    fn test_void_open_after_deposit() {
+        let previous = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+        ];
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::VoidOpen)
+            .then_expect_error(BankAccountError("account has transactions".to_string()))
+    }
+
+    #[test]
+    fn test_close_account_with_payout() {
+        let previous = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+        ];
+        let expected = vec![
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 200.0,
+                balance: 0.0,
+            },
+            BankAccountEvent::AccountClosed {
+                payout_destination: Some("ext-acct-9".to_string()),
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous.clone())
+            .when(BankAccountCommand::CloseAccountWithPayout {
+                destination: "ext-acct-9".to_string(),
+            })
+            .then_expect_events(expected.clone());
+
+        let mut account = BankAccount::default();
+        for event in previous.into_iter().chain(expected) {
+            account.apply(event);
+        }
+        assert_eq!(account.balance, 0.0);
+        assert!(account.closed);
+    }
+
+    #[test]
+    fn test_close_account_with_payout_already_closed() {
+        let previous = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::AccountClosed {
+                payout_destination: Some("ext-acct-9".to_string()),
+            },
+        ];
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::CloseAccountWithPayout {
+                destination: "ext-acct-9".to_string(),
+            })
+            .then_expect_error(BankAccountError("account already closed".to_string()))
+    }
+
+    fn recent_activity(events: Vec<BankAccountEvent>) -> RecentActivityView {
+        let mut view = RecentActivityView::default();
+        for (index, payload) in events.into_iter().enumerate() {
+            view.update(&EventEnvelope {
+                aggregate_id: "acct-1".to_string(),
+                sequence: index + 1,
+                payload,
+                metadata: HashMap::new(),
+            });
+        }
+        view
+    }
+
+    #[test]
+    fn test_deposit_then_withdraw_view() {
+        let deposit = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let withdrawal = BankAccountEvent::CustomerWithdrewCash {
+            amount: 50.0,
+            balance: 150.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposit.clone()])
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(50.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_view_from(
+                recent_activity(vec![deposit.clone()]),
+                recent_activity(vec![deposit, withdrawal]),
+            );
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            })
+            .then_expect_view(recent_activity(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 200.0,
+                    balance: 200.0,
+                },
+            ]));
+    }
+
+    #[test]
+    fn test_receive_wire() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::WireReceived {
+            reference: "FED-20230531-0001".to_string(),
+            amount: 1500.0,
+            sender: "Acme Corp".to_string(),
+            balance: 1700.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous.clone()])
+            .when(BankAccountCommand::ReceiveWire {
+                reference: "FED-20230531-0001".to_string(),
+                amount: 1500.0,
+                sender: "Acme Corp".to_string(),
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        account.apply(previous);
+        account.apply(expected);
+        assert_eq!(account.balance, 1700.0);
+    }
+
+    #[test]
+    fn test_receive_wire_duplicate_reference() {
+        let previous = BankAccountEvent::WireReceived {
+            reference: "FED-20230531-0001".to_string(),
+            amount: 1500.0,
+            sender: "Acme Corp".to_string(),
+            balance: 1500.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::ReceiveWire {
+                reference: "FED-20230531-0001".to_string(),
+                amount: 1500.0,
+                sender: "Acme Corp".to_string(),
+            })
+            .then_expect_error(BankAccountError("duplicate wire".to_string()))
+    }
+
+    fn opened_with_holds(max_holds: usize, holds: usize) -> Vec<BankAccountEvent> {
+        let mut events = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: Some(max_holds),
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+        ];
+        for n in 0..holds {
+            events.push(BankAccountEvent::HoldPlaced {
+                hold_id: format!("hold-{}", n),
+                amount: 10.0,
+                placed_at: may_31(),
+            });
+        }
+        events
+    }
+
+    #[test]
+    fn test_place_hold() {
+        let expected = BankAccountEvent::HoldPlaced {
+            hold_id: "hold-0".to_string(),
+            amount: 10.0,
+            placed_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(opened_with_holds(2, 0))
+            .when(BankAccountCommand::PlaceHold {
+                hold_id: "hold-0".to_string(),
+                amount: 10.0,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in opened_with_holds(2, 0) {
+            account.apply(event);
+        }
+        account.apply(expected);
+        assert_eq!(account.available_balance(), 490.0);
+    }
+
+    #[test]
+    fn test_place_hold_up_to_limit() {
+        let expected = BankAccountEvent::HoldPlaced {
+            hold_id: "hold-1".to_string(),
+            amount: 10.0,
+            placed_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(opened_with_holds(2, 1))
+            .when(BankAccountCommand::PlaceHold {
+                hold_id: "hold-1".to_string(),
+                amount: 10.0,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_place_hold_past_limit() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(opened_with_holds(2, 2))
+            .when(BankAccountCommand::PlaceHold {
+                hold_id: "hold-2".to_string(),
+                amount: 10.0,
+            })
+            .then_expect_error(BankAccountError("too many holds".to_string()))
+    }
+
+    #[test]
+    fn test_dispute_hold_past_limit() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(opened_with_holds(2, 2))
+            .when(BankAccountCommand::HoldDisputedFunds {
+                dispute_id: "dispute-1".to_string(),
+                amount: 10.0,
+            })
+            .then_expect_error(BankAccountError("too many holds".to_string()))
+    }
+
+    #[test]
+    fn test_place_hold_default_limit() {
+        let previous = opened_with_holds(MAX_HOLDS, MAX_HOLDS)
+            .into_iter()
+            .map(|event| match event {
+                BankAccountEvent::AccountOpened { account_id, .. } => {
+                    BankAccountEvent::AccountOpened {
+                        account_id,
+                        max_holds: None,
+                        first_withdrawal_allowed_after: None,
+                        branch_id: None,
+                    }
+                }
+                event => event,
+            })
+            .collect();
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::PlaceHold {
+                hold_id: "one-too-many".to_string(),
+                amount: 10.0,
+            })
+            .then_expect_error(BankAccountError("too many holds".to_string()))
+    }
+
+    #[test]
+    fn test_release_hold_frees_slot() {
+        let mut previous = opened_with_holds(2, 2);
+        previous.push(BankAccountEvent::HoldReleased {
+            hold_id: "hold-0".to_string(),
+            released_at: may_31(),
+        });
+        let expected = BankAccountEvent::HoldPlaced {
+            hold_id: "hold-2".to_string(),
+            amount: 10.0,
+            placed_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(opened_with_holds(2, 2))
+            .when(BankAccountCommand::ReleaseHold {
+                hold_id: "hold-0".to_string(),
+            })
+            .then_expect_events(vec![BankAccountEvent::HoldReleased {
+                hold_id: "hold-0".to_string(),
+                released_at: may_31(),
+            }]);
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::PlaceHold {
+                hold_id: "hold-2".to_string(),
+                amount: 10.0,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_close_and_transfer() {
+        let previous = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+        ];
+        let expected = vec![
+            BankAccountEvent::CustomerTransferredMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 200.0,
+                balance: 0.0,
+            },
+            BankAccountEvent::AccountClosed {
+                payout_destination: None,
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::CloseAndTransfer {
+                to_account_id: "acct-2".to_string(),
+            })
+            .then_expect_events(expected)
+    }
+
+    #[test]
+    fn test_close_and_transfer_already_closed() {
+        let previous = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::AccountClosed {
+                payout_destination: None,
+            },
+        ];
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::CloseAndTransfer {
+                to_account_id: "acct-2".to_string(),
+            })
+            .then_expect_error(BankAccountError("account already closed".to_string()))
+    }
+
+    #[test]
+    fn test_close_and_transfer_with_active_hold() {
+        let previous = opened_with_holds(2, 1);
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(BankAccountCommand::CloseAndTransfer {
+                to_account_id: "acct-2".to_string(),
+            })
+            .then_expect_error(BankAccountError("account has active holds".to_string()))
+    }
+
+    fn charge_authorized() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: 80.0,
+                placed_at: may_31(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_authorize_charge() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::HoldPlaced {
+            hold_id: "auth-1".to_string(),
+            amount: 80.0,
+            placed_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(vec![previous])
+            .when(BankAccountCommand::AuthorizeCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 80.0,
+            })
+            .then_expect_events(vec![expected])
+    }
+
+    #[test]
+    fn test_capture_charge_full() {
+        let expected = BankAccountEvent::ChargeCaptured {
+            auth_id: "auth-1".to_string(),
+            amount: 80.0,
+            balance: 120.0,
+            captured_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(charge_authorized())
+            .when(BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 80.0,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in charge_authorized() {
+            account.apply(event);
+        }
+        account.apply(expected);
+        assert!(account.holds.is_empty());
+        assert_eq!(account.available_balance(), 120.0);
+    }
+
+    #[test]
+    fn test_capture_charge_partial_releases_remainder() {
+        let expected = BankAccountEvent::ChargeCaptured {
+            auth_id: "auth-1".to_string(),
+            amount: 60.0,
+            balance: 140.0,
+            captured_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(charge_authorized())
+            .when(BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 60.0,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in charge_authorized() {
+            account.apply(event);
+        }
+        assert_eq!(account.available_balance(), 120.0);
+        account.apply(expected);
+        assert!(account.holds.is_empty());
+        assert_eq!(account.available_balance(), 140.0);
+    }
+
+    #[test]
+    fn test_capture_charge_exceeds_authorization() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(charge_authorized())
+            .when(BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 80.01,
+            })
+            .then_expect_error(BankAccountError(
+                "capture exceeds authorization".to_string(),
+            ))
+    }
+
+    /// One more than the highest index in `command_index`.
+    const COMMAND_COUNT: usize = 54;
+
+    /// Each command's position in `one_of_each_command`. The match is exhaustive, so a new
+    /// command fails to compile here until it is given the next index; bumping
+    /// `COMMAND_COUNT` then fails `test_one_of_each_command_is_complete` until it is listed.
+    fn command_index(command: &BankAccountCommand) -> usize {
+        match command {
+            BankAccountCommand::OpenAccount { .. } => 0,
+            BankAccountCommand::DepositMoney { .. } => 1,
+            BankAccountCommand::WithdrawMoney { .. } => 2,
+            BankAccountCommand::WriteCheck { .. } => 3,
+            BankAccountCommand::SetCategoryBudget { .. } => 4,
+            BankAccountCommand::ScheduleBillPayment { .. } => 5,
+            BankAccountCommand::CaptureBillPayment { .. } => 6,
+            BankAccountCommand::ApplyBonus { .. } => 7,
+            BankAccountCommand::VoidOpen => 8,
+            BankAccountCommand::CloseAccountWithPayout { .. } => 9,
+            BankAccountCommand::ReceiveWire { .. } => 10,
+            BankAccountCommand::PlaceHold { .. } => 11,
+            BankAccountCommand::ReleaseHold { .. } => 12,
+            BankAccountCommand::CloseAndTransfer { .. } => 13,
+            BankAccountCommand::AuthorizeCharge { .. } => 14,
+            BankAccountCommand::CaptureCharge { .. } => 15,
+            BankAccountCommand::DepositPaycheck { .. } => 16,
+            BankAccountCommand::PostBackdated { .. } => 17,
+            BankAccountCommand::AbsorbAccount { .. } => 18,
+            BankAccountCommand::SetInterestRate { .. } => 19,
+            BankAccountCommand::AccrueInterest { .. } => 20,
+            BankAccountCommand::VerifyKyc { .. } => 21,
+            BankAccountCommand::SetNickname { .. } => 22,
+            BankAccountCommand::SetRoundUp { .. } => 23,
+            BankAccountCommand::RequestStatement { .. } => 24,
+            BankAccountCommand::SetTransactionLimit { .. } => 25,
+            BankAccountCommand::DissolveBucket { .. } => 26,
+            BankAccountCommand::SetRejectionAudit { .. } => 27,
+            BankAccountCommand::CorrectInterest { .. } => 28,
+            BankAccountCommand::SetExpiration { .. } => 29,
+            BankAccountCommand::CloseAccount => 30,
+            BankAccountCommand::HoldDisputedFunds { .. } => 31,
+            BankAccountCommand::ResolveDispute { .. } => 32,
+            BankAccountCommand::PauseInterest => 33,
+            BankAccountCommand::ResumeInterest => 34,
+            BankAccountCommand::TransferBranch { .. } => 35,
+            BankAccountCommand::TransferMoney { .. } => 36,
+            BankAccountCommand::ConfigureSweep { .. } => 37,
+            BankAccountCommand::RecordBalanceSnapshot => 38,
+            BankAccountCommand::VoidDay { .. } => 39,
+            BankAccountCommand::EarnPoints { .. } => 40,
+            BankAccountCommand::RedeemPoints { .. } => 41,
+            BankAccountCommand::FlagForReview { .. } => 42,
+            BankAccountCommand::ClearReview => 43,
+            BankAccountCommand::WithholdTax { .. } => 44,
+            BankAccountCommand::Redenominate { .. } => 45,
+            BankAccountCommand::SpinOff { .. } => 46,
+            BankAccountCommand::TellerDeposit { .. } => 47,
+            BankAccountCommand::TellerWithdrawal { .. } => 48,
+            BankAccountCommand::PlaceGarnishment { .. } => 49,
+            BankAccountCommand::ReleaseGarnishment { .. } => 50,
+            BankAccountCommand::ScheduleRecurringDeposit { .. } => 51,
+            BankAccountCommand::CancelRecurringDeposit { .. } => 52,
+            BankAccountCommand::RunRecurringDeposit { .. } => 53,
+        }
+    }
+
+    /// One instance of every command, in `command_index` order, for tests that must cover
+    /// them all.
+    fn one_of_each_command() -> Vec<BankAccountCommand> {
+        vec![
+            BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: None,
+            },
+            BankAccountCommand::DepositMoney {
+                amount: positive(10.0),
+            },
+            BankAccountCommand::WithdrawMoney {
+                amount: positive(10.0),
+                category: None,
+                mcc: None,
+            },
+            BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 10.0,
+                category: None,
+                mcc: None,
+            },
+            BankAccountCommand::SetCategoryBudget {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+            BankAccountCommand::ScheduleBillPayment {
+                payee: "water-co".to_string(),
+                amount: 10.0,
+                due: Date::from_ymd(2023, 6, 5).unwrap(),
+            },
+            BankAccountCommand::CaptureBillPayment {
+                payee: "power-co".to_string(),
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+            BankAccountCommand::ApplyBonus {
+                bonus_id: "spring-promo".to_string(),
+                amount: 10.0,
+                min_balance: 0.0,
+            },
+            BankAccountCommand::VoidOpen,
+            BankAccountCommand::CloseAccountWithPayout {
+                destination: "ext-acct-9".to_string(),
+            },
+            BankAccountCommand::ReceiveWire {
+                reference: "FED-20230531-0001".to_string(),
+                amount: 10.0,
+                sender: "Acme Corp".to_string(),
+            },
+            BankAccountCommand::PlaceHold {
+                hold_id: "hold-1".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::ReleaseHold {
+                hold_id: "auth-1".to_string(),
+            },
+            BankAccountCommand::CloseAndTransfer {
+                to_account_id: "acct-2".to_string(),
+            },
+            BankAccountCommand::AuthorizeCharge {
+                auth_id: "auth-2".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::DepositPaycheck {
+                amount: 10.0,
+                rules: vec![("savings".to_string(), 100.0)],
+            },
+            BankAccountCommand::PostBackdated {
+                amount: 10.0,
+                effective_date: Date::from_ymd(2023, 5, 1).unwrap(),
+                reason: "reconciliation".to_string(),
+            },
+            BankAccountCommand::AbsorbAccount {
+                from_account_id: "acct-3".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::SetInterestRate { annual_rate: 0.05 },
+            BankAccountCommand::AccrueInterest { days: 30 },
+            BankAccountCommand::VerifyKyc {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountCommand::SetNickname {
+                nickname: "Rainy day".to_string(),
+            },
+            BankAccountCommand::SetRoundUp { enabled: true },
+            BankAccountCommand::RequestStatement {
+                from: Date::from_ymd(2023, 5, 1).unwrap(),
+                to: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+            BankAccountCommand::SetTransactionLimit {
+                max_transaction: 500.0,
+            },
+            BankAccountCommand::DissolveBucket {
+                name: "savings".to_string(),
+            },
+            BankAccountCommand::SetRejectionAudit { enabled: true },
+            BankAccountCommand::CorrectInterest {
+                original_sequence: 2,
+                corrected_amount: 1.0,
+                reason: "wrong rate".to_string(),
+            },
+            BankAccountCommand::SetExpiration {
+                expires: Date::from_ymd(2023, 12, 31).unwrap(),
+            },
+            BankAccountCommand::CloseAccount,
+            BankAccountCommand::HoldDisputedFunds {
+                dispute_id: "dispute-1".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::ResolveDispute {
+                dispute_id: "dispute-1".to_string(),
+                in_customer_favor: true,
+            },
+            BankAccountCommand::PauseInterest,
+            BankAccountCommand::ResumeInterest,
+            BankAccountCommand::TransferBranch {
+                new_branch_id: "branch-2".to_string(),
+            },
+            BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::ConfigureSweep {
+                target_balance: 100.0,
+                sweep_to: "invest-1".to_string(),
+            },
+            BankAccountCommand::RecordBalanceSnapshot,
+            BankAccountCommand::VoidDay {
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+            BankAccountCommand::EarnPoints {
+                points: 100,
+                reason: "signup".to_string(),
+            },
+            BankAccountCommand::RedeemPoints { points: 50 },
+            BankAccountCommand::FlagForReview {
+                reason: "velocity".to_string(),
+                severity: ReviewSeverity::High,
+            },
+            BankAccountCommand::ClearReview,
+            BankAccountCommand::WithholdTax {
+                amount: 1.0,
+                tax_year: 2023,
+            },
+            BankAccountCommand::Redenominate {
+                new_currency: "NEW".to_string(),
+                ratio: 0.001,
+            },
+            BankAccountCommand::SpinOff {
+                new_account_id: "acct-4".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::TellerDeposit {
+                teller_id: "teller-1".to_string(),
+                amount: positive(10.0),
+            },
+            BankAccountCommand::TellerWithdrawal {
+                teller_id: "teller-1".to_string(),
+                amount: positive(10.0),
+            },
+            BankAccountCommand::PlaceGarnishment {
+                case_number: "case-1".to_string(),
+                amount: 10.0,
+            },
+            BankAccountCommand::ReleaseGarnishment {
+                case_number: "case-1".to_string(),
+            },
+            BankAccountCommand::ScheduleRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+                amount: positive(25.0),
+                interval_days: 7,
+                next_run: Date::from_ymd(2023, 6, 7).unwrap(),
+            },
+            BankAccountCommand::CancelRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+            },
+            BankAccountCommand::RunRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+                due: Date::from_ymd(2023, 6, 7).unwrap(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_closed_account_rejects_every_command() {
+        let previous = vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: 50.0,
+                placed_at: may_31(),
+            },
+            BankAccountEvent::BillPaymentScheduled {
+                payee: "power-co".to_string(),
+                amount: 20.0,
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+            BankAccountEvent::AccountClosed {
+                payout_destination: None,
+            },
+        ];
+        let closed = || BankAccountError("account closed".to_string());
+        let already_closed = || BankAccountError("account already closed".to_string());
+        for command in one_of_each_command() {
+            let closing = matches!(
+                command,
+                BankAccountCommand::CloseAccount
+                    | BankAccountCommand::CloseAccountWithPayout { .. }
+                    | BankAccountCommand::CloseAndTransfer { .. }
+            );
+            let expected_error = if closing { already_closed() } else { closed() };
+            AccountTestFramework::with(BankAccountServices::default())
+                .given(previous.clone())
+                .when(command)
+                .then_expect_error(expected_error);
+        }
+    }
+
+    #[test]
+    fn test_one_of_each_command_is_complete() {
+        let commands = one_of_each_command();
+        assert_eq!(commands.len(), COMMAND_COUNT);
+        for (position, command) in commands.iter().enumerate() {
+            assert_eq!(
+                command_index(command),
+                position,
+                "{} is out of place",
+                command.command_type()
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_command_has_an_outcome() {
+        let commands = one_of_each_command();
+        let command_types: HashSet<&str> = commands
+            .iter()
+            .map(BankAccountCommand::command_type)
+            .collect();
+        assert_eq!(command_types.len(), commands.len());
+        // The refusals expected against this history; every other command must emit events.
+        let refusals = HashMap::from([
+            ("CaptureBillPayment", "no scheduled payment found"),
+            ("VoidOpen", "account not opened"),
+            ("CloseAccountWithPayout", "account has active holds"),
+            ("CloseAndTransfer", "account has active holds"),
+            ("AccrueInterest", "account not interest-bearing"),
+            ("DissolveBucket", "bucket not found"),
+            ("CorrectInterest", "interest posting not found"),
+            ("SetExpiration", "expiration in the past"),
+            ("CloseAccount", "account has active holds"),
+            ("ResolveDispute", "dispute not found"),
+            ("VoidDay", "no transactions to void"),
+            ("RedeemPoints", "insufficient points"),
+            ("ClearReview", "account not under review"),
+            ("ReleaseGarnishment", "garnishment not found"),
+            ("ScheduleRecurringDeposit", "next run in the past"),
+            ("CancelRecurringDeposit", "recurring deposit not found"),
+            ("RunRecurringDeposit", "recurring deposit not found"),
+        ]);
+
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: 50.0,
+                placed_at: may_31(),
+            },
+        ];
+        for command in commands {
+            let command_type = command.command_type();
+            let result = AccountTestFramework::with(BankAccountServices::default())
+                .given(previous.clone())
+                .when(command)
+                .inspect_result();
+            // Either events are emitted or the command is refused for the expected reason;
+            // nothing may be silently accepted and dropped.
+            match (result, refusals.get(command_type)) {
+                (Ok(events), None) => {
+                    assert!(!events.is_empty(), "{} emitted no events", command_type)
+                }
+                (Ok(_), Some(reason)) => panic!("{} was not refused: {}", command_type, reason),
+                (Err(BankAccountError(reason)), expected) => {
+                    assert_eq!(Some(&reason.as_str()), expected, "{}", command_type)
+                }
+            }
+        }
+    }
+
+    fn hours(hours: u64) -> Duration {
+        Duration::from_secs(hours * 3_600)
+    }
+
+    #[test]
+    fn test_average_hold() {
+        let mut account = BankAccount::default();
+        for event in [
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            // Placed before the window opens and released one hour into it.
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-a".to_string(),
+                amount: 20.0,
+                placed_at: may_31() - hours(5),
+            },
+            BankAccountEvent::HoldReleased {
+                hold_id: "hold-a".to_string(),
+                released_at: may_31() + hours(1),
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-b".to_string(),
+                amount: 100.0,
+                placed_at: may_31(),
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-c".to_string(),
+                amount: 50.0,
+                placed_at: may_31() + hours(2),
+            },
+            BankAccountEvent::HoldReleased {
+                hold_id: "hold-b".to_string(),
+                released_at: may_31() + hours(5),
+            },
+        ] {
+            account.apply(event);
+        }
+
+        // (20 * 1h + 100 * 5h + 50 * 8h) / 10h
+        let average = account.average_hold(hours(10), may_31() + hours(10));
+        assert!((average - 92.0).abs() < 1e-9);
+
+        // Only hold-c overlaps the last two hours.
+        let average = account.average_hold(hours(2), may_31() + hours(10));
+        assert!((average - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_average_hold_after_capture() {
+        let mut account = BankAccount::default();
+        for event in [
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: 80.0,
+                placed_at: may_31(),
+            },
+            BankAccountEvent::ChargeCaptured {
+                auth_id: "auth-1".to_string(),
+                amount: 60.0,
+                balance: 440.0,
+                captured_at: may_31() + hours(3),
+            },
+        ] {
+            account.apply(event);
+        }
+
+        // 80 * 3h / 4h
+        let average = account.average_hold(hours(4), may_31() + hours(4));
+        assert!((average - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hold_history_drops_old_releases() {
+        let mut account = BankAccount::default();
+        for (hold_id, placed_at) in [("hold-1", may_31()), ("hold-2", may_31() + hours(24 * 100))] {
+            account.apply(BankAccountEvent::HoldPlaced {
+                hold_id: hold_id.to_string(),
+                amount: 40.0,
+                placed_at,
+            });
+            account.apply(BankAccountEvent::HoldReleased {
+                hold_id: hold_id.to_string(),
+                released_at: placed_at + hours(1),
+            });
+        }
+
+        assert_eq!(account.hold_history.len(), 1);
+        assert_eq!(account.hold_history[0].hold_id, "hold-2");
+    }
+
+    fn paycheck_split() -> Vec<(String, f64)> {
+        vec![
+            ("spending".to_string(), 60.0),
+            ("savings".to_string(), 40.0),
+        ]
+    }
+
+    #[test]
+    fn test_deposit_paycheck_split() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::DepositPaycheck {
+                amount: 2_000.0,
+                rules: paycheck_split(),
+            })
+            .then_expect_events(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 2_000.0,
+                    balance: 2_000.0,
+                },
+                BankAccountEvent::PaycheckAllocated {
+                    bucket: "spending".to_string(),
+                    amount: 1_200.0,
+                },
+                BankAccountEvent::PaycheckAllocated {
+                    bucket: "savings".to_string(),
+                    amount: 800.0,
+                },
+            ]);
+    }
+
+    #[test]
+    fn test_deposit_paycheck_split_must_total_100() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::DepositPaycheck {
+                amount: 2_000.0,
+                rules: vec![
+                    ("spending".to_string(), 60.0),
+                    ("savings".to_string(), 30.0),
+                ],
+            })
+            .then_expect_error(BankAccountError("invalid split".to_string()));
+    }
+
+    #[test]
+    fn test_deposit_paycheck_split_rejects_nan() {
+        // A NaN percent makes the total NaN, which no tolerance comparison catches.
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::DepositPaycheck {
+                amount: 2_000.0,
+                rules: vec![
+                    ("spending".to_string(), 100.0),
+                    ("savings".to_string(), f64::NAN),
+                ],
+            })
+            .then_expect_error(BankAccountError("invalid split".to_string()));
+    }
+
+    #[test]
+    fn test_paycheck_bucket_balances() {
+        let mut account = BankAccount::default();
+        for event in [
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 2_000.0,
+                balance: 2_000.0,
+            },
+            BankAccountEvent::PaycheckAllocated {
+                bucket: "spending".to_string(),
+                amount: 1_200.0,
+            },
+            BankAccountEvent::PaycheckAllocated {
+                bucket: "savings".to_string(),
+                amount: 800.0,
+            },
+            BankAccountEvent::PaycheckAllocated {
+                bucket: "savings".to_string(),
+                amount: 500.0,
+            },
+        ] {
+            account.apply(event);
+        }
+
+        assert_eq!(account.bucket_balance("spending"), 1_200.0);
+        assert_eq!(account.bucket_balance("savings"), 1_300.0);
+        assert_eq!(account.bucket_balance("vacation"), 0.0);
+    }
+
+    #[test]
+    fn test_post_backdated_credit() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::BackdatedTransactionPosted {
+            amount: 35.0,
+            effective_date: Date::from_ymd(2023, 5, 12).unwrap(),
+            reason: "missed interest credit".to_string(),
+            balance: 235.0,
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(vec![previous])
+            .when(BankAccountCommand::PostBackdated {
+                amount: 35.0,
+                effective_date: Date::from_ymd(2023, 5, 12).unwrap(),
+                reason: "missed interest credit".to_string(),
+            })
+            .then_expect_events(vec![expected]);
+    }
+
+    #[test]
+    fn test_post_backdated_rejects_future_date() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given_no_previous_events()
+            .when(BankAccountCommand::PostBackdated {
+                amount: 35.0,
+                effective_date: Date::from_ymd(2023, 6, 1).unwrap(),
+                reason: "missed interest credit".to_string(),
+            })
+            .then_expect_error(BankAccountError("future effective date".to_string()));
+    }
+
+    #[test]
+    fn test_post_backdated_rejects_invalid_amount() {
+        for (amount, reason) in [
+            (0.0, "amount must be positive: 0"),
+            (f64::NAN, "amount must be positive: NaN"),
+        ] {
+            AccountTestFramework::with(services_on(2023, 5, 31))
+                .given_no_previous_events()
+                .when(BankAccountCommand::PostBackdated {
+                    amount,
+                    effective_date: Date::from_ymd(2023, 5, 12).unwrap(),
+                    reason: "missed interest credit".to_string(),
+                })
+                .then_expect_error(BankAccountError(reason.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_absorb_account() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = BankAccountEvent::BalanceAbsorbed {
+            from_account_id: "acct-dup".to_string(),
+            amount: 75.0,
+            balance: 275.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous.clone()])
+            .when(BankAccountCommand::AbsorbAccount {
+                from_account_id: "acct-dup".to_string(),
+                amount: 75.0,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        account.apply(previous);
+        account.apply(expected);
+        assert_eq!(account.balance, 275.0);
+    }
+
+    #[test]
+    fn test_absorb_account_rejects_duplicate_source() {
+        let previous = BankAccountEvent::BalanceAbsorbed {
+            from_account_id: "acct-dup".to_string(),
+            amount: 75.0,
+            balance: 75.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::AbsorbAccount {
+                from_account_id: "acct-dup".to_string(),
+                amount: 75.0,
+            })
+            .then_expect_error(BankAccountError("account already absorbed".to_string()));
+    }
+
+    #[test]
+    fn test_set_interest_rate() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetInterestRate { annual_rate: 0.05 })
+            .then_expect_events(vec![BankAccountEvent::InterestRateChanged {
+                annual_rate: 0.05,
+            }]);
+    }
+
+    #[test]
+    fn test_accrue_interest_at_stored_rate() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::InterestRateChanged { annual_rate: 0.05 },
+        ];
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::AccrueInterest { days: 73 })
+            .then_expect_events(vec![BankAccountEvent::InterestAccrued {
+                amount: 10.0,
+                balance: 1_010.0,
+            }]);
+    }
+
+    #[test]
+    fn test_accrue_interest_requires_rate() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 1_000.0,
+            balance: 1_000.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::AccrueInterest { days: 73 })
+            .then_expect_error(BankAccountError("account not interest-bearing".to_string()));
+    }
+
+    #[test]
+    fn test_set_interest_rate_too_high() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetInterestRate { annual_rate: 5.0 })
+            .then_expect_error(BankAccountError("rate too high".to_string()));
+    }
+
+    #[test]
+    fn test_ledger_and_available_balance() {
+        let mut account = BankAccount::default();
+        for event in [
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-1".to_string(),
+                amount: 120.0,
+                placed_at: may_31(),
+            },
+            BankAccountEvent::BillPaymentScheduled {
+                payee: "power-co".to_string(),
+                amount: 80.0,
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+        ] {
+            account.apply(event);
+        }
+
+        assert_eq!(account.ledger_balance(), 500.0);
+        assert_eq!(account.available_balance(), 300.0);
+    }
+
+    fn opened() -> BankAccountEvent {
+        BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds: None,
+            first_withdrawal_allowed_after: None,
+            branch_id: None,
+        }
+    }
+
+    #[test]
+    fn test_transactions_require_kyc() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![opened()])
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            })
+            .then_expect_error(BankAccountError("kyc required".to_string()));
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![opened()])
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(10.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError("kyc required".to_string()));
+    }
+
+    #[test]
+    fn test_transactions_allowed_after_kyc() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![opened()])
+            .when(BankAccountCommand::VerifyKyc {
+                verification_id: "kyc-1".to_string(),
+            })
+            .then_expect_events(vec![BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            }]);
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(vec![
+                opened(),
+                BankAccountEvent::KycVerified {
+                    verification_id: "kyc-1".to_string(),
+                },
+            ])
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            }]);
+    }
+
+    #[test]
+    fn test_event_json_is_camel_case() {
+        let event = BankAccountEvent::HoldPlaced {
+            hold_id: "hold-1".to_string(),
+            amount: 40.0,
+            placed_at: may_31(),
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["HoldPlaced"]["holdId"], "hold-1");
+        assert!(json["HoldPlaced"].get("hold_id").is_none());
+
+        let restored: BankAccountEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, event);
+    }
+
+    #[test]
+    fn test_snake_case_json_still_loads() {
+        let event: BankAccountEvent = serde_json::from_str(
+            r#"{"CustomerWroteCheck":{"check_number":"1170","amount":50.0,"balance":150.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            event,
+            BankAccountEvent::CustomerWroteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                balance: 150.0,
+            }
+        );
+
+        let command: BankAccountCommand =
+            serde_json::from_str(r#"{"CloseAndTransfer":{"to_account_id":"acct-2"}}"#).unwrap();
+        assert_eq!(
+            command,
+            BankAccountCommand::CloseAndTransfer {
+                to_account_id: "acct-2".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_nickname() {
+        let expected = BankAccountEvent::NicknameChanged {
+            nickname: "Rainy day fund".to_string(),
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetNickname {
+                nickname: "Rainy day fund".to_string(),
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        account.apply(expected);
+        assert_eq!(account.nickname(), Some("Rainy day fund"));
+    }
+
+    #[test]
+    fn test_set_nickname_too_long() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetNickname {
+                nickname: "x".repeat(MAX_NICKNAME_LENGTH + 1),
+            })
+            .then_expect_error(BankAccountError("invalid nickname".to_string()));
+    }
+
+    #[test]
+    fn test_set_nickname_with_control_characters() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetNickname {
+                nickname: "Rainy\nday".to_string(),
+            })
+            .then_expect_error(BankAccountError("invalid nickname".to_string()));
+    }
+
+    fn round_up_enabled() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 100.0,
+                balance: 100.0,
+            },
+            BankAccountEvent::RoundUpChanged { enabled: true },
+        ]
+    }
+
+    #[test]
+    fn test_withdrawal_round_up_saved() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(round_up_enabled())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(4.30),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 4.30,
+                    balance: 95.7,
+                },
+                BankAccountEvent::RoundUpSaved {
+                    amount: 0.7,
+                    balance: 95.7,
+                },
+            ]);
+
+        let mut account = BankAccount::default();
+        for event in round_up_enabled() {
+            account.apply(event);
+        }
+        account.apply(BankAccountEvent::CustomerWithdrewCash {
+            amount: 4.30,
+            balance: 95.7,
+        });
+        account.apply(BankAccountEvent::RoundUpSaved {
+            amount: 0.7,
+            balance: 95.7,
+        });
+        assert_eq!(account.ledger_balance(), 95.7);
+        assert_eq!(account.bucket_balance(ROUND_UP_BUCKET), 0.7);
+    }
+
+    #[test]
+    fn test_whole_dollar_withdrawal_saves_nothing() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(round_up_enabled())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(5.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 5.0,
+                balance: 95.0,
+            }]);
+    }
+
+    #[test]
+    fn test_round_up_skipped_when_funds_short() {
+        // 0.25 is left after the withdrawal, short of the 0.75 round-up.
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 10.5,
+                    balance: 10.5,
+                },
+                BankAccountEvent::RoundUpChanged { enabled: true },
+            ])
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(10.25),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 10.25,
+                balance: 0.25,
+            }]);
+    }
+
+    #[test]
+    fn test_request_statement() {
+        AccountTestFramework::with(services_on(2023, 6, 1))
+            .given_no_previous_events()
+            .when(BankAccountCommand::RequestStatement {
+                from: Date::from_ymd(2023, 5, 1).unwrap(),
+                to: Date::from_ymd(2023, 5, 31).unwrap(),
+            })
+            .then_expect_events(vec![BankAccountEvent::StatementRequested {
+                from: Date::from_ymd(2023, 5, 1).unwrap(),
+                to: Date::from_ymd(2023, 5, 31).unwrap(),
+                requested_at: Date::from_ymd(2023, 6, 1).unwrap().start_of_day(),
+            }]);
+    }
+
+    #[test]
+    fn test_request_statement_inverted_range() {
+        AccountTestFramework::with(services_on(2023, 6, 1))
+            .given_no_previous_events()
+            .when(BankAccountCommand::RequestStatement {
+                from: Date::from_ymd(2023, 5, 31).unwrap(),
+                to: Date::from_ymd(2023, 5, 1).unwrap(),
+            })
+            .then_expect_error(BankAccountError("invalid date range".to_string()));
+    }
+
+    fn transaction_limit(max_transaction: f64) -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::TransactionLimitSet { max_transaction },
+        ]
+    }
+
+    #[test]
+    fn test_transaction_at_limit_allowed() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(transaction_limit(500.0))
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(500.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 500.0,
+                balance: 500.0,
+            }]);
+    }
+
+    #[test]
+    fn test_transaction_over_limit_rejected() {
+        let too_large = || BankAccountError("transaction too large, limit: 500".to_string());
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(transaction_limit(500.0))
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(500.01),
+            })
+            .then_expect_error(too_large());
+
+        AccountTestFramework::with(check_services(Ok(())))
+            .given(transaction_limit(500.0))
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 600.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(too_large());
+    }
+
+    #[test]
+    fn test_transaction_limit_disabled() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(transaction_limit(0.0))
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(900.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 900.0,
+                balance: 100.0,
+            }]);
+    }
+
+    fn check_written() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::CustomerWroteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                balance: 150.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_write_check_rejects_duplicate_number() {
+        AccountTestFramework::with(check_services(Ok(())))
+            .given(check_written())
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 20.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError("duplicate check number".to_string()));
+    }
+
+    #[test]
+    fn test_write_check_with_new_number() {
+        AccountTestFramework::with(check_services(Ok(())))
+            .given(check_written())
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1171".to_string(),
+                amount: 20.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWroteCheck {
+                check_number: "1171".to_string(),
+                amount: 20.0,
+                balance: 130.0,
+            }]);
+    }
+
+    fn savings_funded() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 2_000.0,
+                balance: 2_000.0,
+            },
+            BankAccountEvent::PaycheckAllocated {
+                bucket: "savings".to_string(),
+                amount: 800.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_dissolve_bucket() {
+        let expected = BankAccountEvent::BucketDissolved {
+            name: "savings".to_string(),
+            amount: 800.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(savings_funded())
+            .when(BankAccountCommand::DissolveBucket {
+                name: "savings".to_string(),
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in savings_funded() {
+            account.apply(event);
+        }
+        assert_eq!(account.unallocated_balance(), 1_200.0);
+
+        account.apply(expected);
+        assert_eq!(account.unallocated_balance(), 2_000.0);
+        assert_eq!(account.bucket_balance("savings"), 0.0);
+    }
+
+    #[test]
+    fn test_dissolve_unknown_bucket() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(savings_funded())
+            .when(BankAccountCommand::DissolveBucket {
+                name: "vacation".to_string(),
+            })
+            .then_expect_error(BankAccountError("bucket not found".to_string()));
+    }
+
+    #[test]
+    fn test_withdraw_full_balance_despite_rounding() {
+        // 0.7 + 0.1 is a hair under 0.8 in binary floating point.
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.7,
+                balance: 0.7,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.7 + 0.1,
+            },
+        ];
+
+        let result = AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(0.8),
+                category: None,
+                mcc: None,
+            })
+            .inspect_result();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_withdraw_thirty_cents_after_three_dimes() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.1,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.1 + 0.1,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.1 + 0.1 + 0.1,
+            },
+        ];
+
+        let result = AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(0.3),
+                category: None,
+                mcc: None,
+            })
+            .inspect_result()
+            .unwrap();
+
+        match result.as_slice() {
+            [BankAccountEvent::CustomerWithdrewCash { amount, balance }] => {
+                assert_eq!(*amount, 0.3);
+                assert!(balance.abs() < FUNDS_EPSILON);
+            }
+            events => panic!("unexpected events: {:?}", events),
+        }
+    }
+
+    #[test]
+    fn test_audited_rejection_is_recorded() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 100.0,
+                balance: 100.0,
+            },
+            BankAccountEvent::RejectionAuditChanged { enabled: true },
+        ];
+        let expected = BankAccountEvent::CommandRejected {
+            command_type: "WithdrawMoney".to_string(),
+            reason: "funds not available, shortfall: 400".to_string(),
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous.clone())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(500.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in previous.into_iter().chain([expected]) {
+            account.apply(event);
+        }
+        assert_eq!(account.balance, 100.0);
+    }
+
+    #[test]
+    fn test_audit_returns_other_errors() {
+        let audited = || {
+            vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 100.0,
+                    balance: 100.0,
+                },
+                BankAccountEvent::RejectionAuditChanged { enabled: true },
+            ]
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(audited())
+            .when(BankAccountCommand::SetCategoryBudget {
+                category: "dining".to_string(),
+                limit: -1.0,
+            })
+            .then_expect_error(BankAccountError(
+                "budget limit must not be negative".to_string(),
+            ));
+
+        let mut closed = audited();
+        closed.push(BankAccountEvent::AccountClosed {
+            payout_destination: None,
+        });
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(closed)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(500.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError("account closed".to_string()));
+    }
+
+    #[test]
+    fn test_rejection_returned_without_audit() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::RejectionAuditChanged {
+                enabled: false,
+            }])
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(500.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 500".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_every_audited_rejection_is_recorded() {
+        let withdraw = |amount, category: Option<&str>| BankAccountCommand::WithdrawMoney {
+            amount: positive(amount),
+            category: category.map(str::to_string),
+            mcc: None,
+        };
+        let deposited = BankAccountEvent::CustomerDepositedMoney {
+            amount: 500.0,
+            balance: 500.0,
+        };
+        let opened = |max_holds| BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds,
+            first_withdrawal_allowed_after: None,
+            branch_id: None,
+        };
+        let verified = BankAccountEvent::KycVerified {
+            verification_id: "kyc-1".to_string(),
+        };
+
+        for reason in AUDITED_REJECTIONS {
+            let (mut previous, command) = match reason {
+                KYC_REQUIRED => (vec![opened(None)], withdraw(50.0, None)),
+                UNDER_REVIEW => (flagged(), withdraw(600.0, None)),
+                HOLDING_PERIOD_ACTIVE => (in_holding_period(), withdraw(50.0, None)),
+                FUNDS_NOT_AVAILABLE => (vec![], withdraw(50.0, None)),
+                FUNDS_GARNISHED => (
+                    vec![
+                        deposited.clone(),
+                        BankAccountEvent::HoldPlaced {
+                            hold_id: "auth-1".to_string(),
+                            amount: 400.0,
+                            placed_at: may_31(),
+                        },
+                        BankAccountEvent::GarnishmentPlaced {
+                            case_number: "CV-2023-118".to_string(),
+                            amount: 300.0,
+                            placed_at: may_31(),
+                        },
+                    ],
+                    BankAccountCommand::CaptureCharge {
+                        auth_id: "auth-1".to_string(),
+                        amount: 400.0,
+                    },
+                ),
+                TRANSACTION_TOO_LARGE => (
+                    vec![
+                        deposited.clone(),
+                        BankAccountEvent::TransactionLimitSet {
+                            max_transaction: 100.0,
+                        },
+                    ],
+                    withdraw(200.0, None),
+                ),
+                BUDGET_EXCEEDED => (
+                    vec![
+                        deposited.clone(),
+                        BankAccountEvent::CategoryBudgetSet {
+                            category: "dining".to_string(),
+                            limit: 100.0,
+                        },
+                    ],
+                    withdraw(200.0, Some("dining")),
+                ),
+                TOO_MANY_HOLDS => (
+                    vec![opened(Some(0)), verified.clone(), deposited.clone()],
+                    BankAccountCommand::PlaceHold {
+                        hold_id: "auth-1".to_string(),
+                        amount: 50.0,
+                    },
+                ),
+                reason => panic!("no scenario for audited rejection: {}", reason),
+            };
+            previous.push(BankAccountEvent::RejectionAuditChanged { enabled: true });
+            let command_type = command.command_type();
+
+            let result = AccountTestFramework::with(
+                services_on(2023, 6, 4).with_review_withdrawal_limit(500.0),
+            )
+            .given(previous)
+            .when(command)
+            .inspect_result();
+
+            match result.as_deref() {
+                Ok(
+                    [BankAccountEvent::CommandRejected {
+                        command_type: rejected,
+                        reason: recorded,
+                    }],
+                ) => {
+                    assert_eq!(rejected, command_type);
+                    assert!(recorded.starts_with(reason), "{}: {}", reason, recorded);
+                }
+                result => panic!("{}: unexpected result {:?}", reason, result),
+            }
+        }
+    }
+
+    fn interest_over_posted() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::InterestRateChanged { annual_rate: 0.5 },
+            BankAccountEvent::InterestAccrued {
+                amount: 100.0,
+                balance: 1_100.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_correct_interest() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(interest_over_posted())
+            .when(BankAccountCommand::CorrectInterest {
+                original_sequence: 3,
+                corrected_amount: 10.0,
+                reason: "rate entered as 50% instead of 5%".to_string(),
+            })
+            .then_expect_events(vec![BankAccountEvent::InterestCorrected {
+                original_sequence: 3,
+                original_amount: 100.0,
+                corrected_amount: 10.0,
+                reason: "rate entered as 50% instead of 5%".to_string(),
+                balance: 1_010.0,
+            }]);
+    }
+
+    #[test]
+    fn test_correct_interest_only_once() {
+        let mut previous = interest_over_posted();
+        previous.push(BankAccountEvent::InterestCorrected {
+            original_sequence: 3,
+            original_amount: 100.0,
+            corrected_amount: 10.0,
+            reason: "rate entered as 50% instead of 5%".to_string(),
+            balance: 1_010.0,
+        });
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::CorrectInterest {
+                original_sequence: 3,
+                corrected_amount: 10.0,
+                reason: "rate entered as 50% instead of 5%".to_string(),
+            })
+            .then_expect_error(BankAccountError("interest already corrected".to_string()));
+    }
+
+    #[test]
+    fn test_correct_interest_requires_interest_posting() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(interest_over_posted())
+            .when(BankAccountCommand::CorrectInterest {
+                original_sequence: 1,
+                corrected_amount: 10.0,
+                reason: "not an interest posting".to_string(),
+            })
+            .then_expect_error(BankAccountError("interest posting not found".to_string()));
+    }
+
+    #[test]
+    fn test_correct_interest_rejects_negative_amount() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(interest_over_posted())
+            .when(BankAccountCommand::CorrectInterest {
+                original_sequence: 3,
+                corrected_amount: -1_000.0,
+                reason: "sign flipped".to_string(),
+            })
+            .then_expect_error(BankAccountError(
+                "corrected interest must not be negative".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_correct_interest_after_interest_spent() {
+        let mut previous = interest_over_posted();
+        previous.push(BankAccountEvent::CustomerWithdrewCash {
+            amount: 1_050.0,
+            balance: 50.0,
+        });
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::CorrectInterest {
+                original_sequence: 3,
+                corrected_amount: 10.0,
+                reason: "rate entered as 50% instead of 5%".to_string(),
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 40".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_set_expiration() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetExpiration {
+                expires: Date::from_ymd(2023, 8, 31).unwrap(),
+            })
+            .then_expect_events(vec![BankAccountEvent::ExpirationSet {
+                expires: Date::from_ymd(2023, 8, 31).unwrap(),
+            }]);
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given_no_previous_events()
+            .when(BankAccountCommand::SetExpiration {
+                expires: Date::from_ymd(2023, 5, 30).unwrap(),
+            })
+            .then_expect_error(BankAccountError("expiration in the past".to_string()));
+    }
+
+    #[test]
+    fn test_expiration_commands() {
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::ExpirationSet {
+            expires: Date::from_ymd(2023, 8, 31).unwrap(),
+        });
+
+        let before = Date::from_ymd(2023, 8, 30).unwrap();
+        assert!(!account.is_expired(before));
+        assert!(account.expiration_commands(before).is_empty());
+
+        let on_expiry = Date::from_ymd(2023, 8, 31).unwrap();
+        assert!(account.is_expired(on_expiry));
+        assert_eq!(
+            account.expiration_commands(on_expiry),
+            vec![BankAccountCommand::CloseAccount]
+        );
+
+        // An expired account still holding funds waits for them to be paid out.
+        account.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 20.0,
+            balance: 20.0,
+        });
+        assert!(account.expiration_commands(on_expiry).is_empty());
+    }
+
+    #[test]
+    fn test_close_account_with_rounding_dust() {
+        let mut previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.1,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.1 + 0.1,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 0.1,
+                balance: 0.1 + 0.1 + 0.1,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 0.3,
+                balance: 0.1 + 0.1 + 0.1 - 0.3,
+            },
+        ];
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous.clone())
+            .when(BankAccountCommand::CloseAccount)
+            .then_expect_events(vec![BankAccountEvent::AccountClosed {
+                payout_destination: None,
+            }]);
+
+        previous.push(BankAccountEvent::ExpirationSet {
+            expires: Date::from_ymd(2023, 8, 31).unwrap(),
+        });
+        let mut account = BankAccount::default();
+        for event in previous {
+            account.apply(event);
+        }
+        assert_eq!(
+            account.expiration_commands(Date::from_ymd(2023, 8, 31).unwrap()),
+            vec![BankAccountCommand::CloseAccount]
+        );
+    }
+
+    #[test]
+    fn test_close_account_requires_zero_balance() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 20.0,
+            balance: 20.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::CloseAccount)
+            .then_expect_error(BankAccountError("account has funds".to_string()));
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::CloseAccount)
+            .then_expect_events(vec![BankAccountEvent::AccountClosed {
+                payout_destination: None,
+            }]);
+    }
+
+    #[test]
+    fn test_positive_amount() {
+        assert_eq!(PositiveAmount::new(0.01).unwrap().value(), 0.01);
+        assert_eq!(
+            PositiveAmount::new(-5.0),
+            Err(BankAccountError("amount must be positive: -5".to_string()))
+        );
+        assert!(PositiveAmount::new(0.0).is_err());
+        assert!(PositiveAmount::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_deposit_command_rejects_negative_amount() {
+        let command: BankAccountCommand =
+            serde_json::from_str(r#"{"DepositMoney":{"amount":200.0}}"#).unwrap();
+        assert_eq!(
+            command,
+            BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            }
+        );
+
+        let result =
+            serde_json::from_str::<BankAccountCommand>(r#"{"DepositMoney":{"amount":-200.0}}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_amount_accepts_integer_and_float_numbers() {
+        let command: BankAccountCommand =
+            serde_json::from_str(r#"{"DepositMoney":{"amount":200}}"#).unwrap();
+        assert_eq!(
+            command,
+            BankAccountCommand::DepositMoney {
+                amount: positive(200.0),
+            }
+        );
+
+        let command: BankAccountCommand =
+            serde_json::from_str(r#"{"PlaceHold":{"holdId":"hold-1","amount":12.5}}"#).unwrap();
+        assert_eq!(
+            command,
+            BankAccountCommand::PlaceHold {
+                hold_id: "hold-1".to_string(),
+                amount: 12.5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_amount_rejects_strings() {
+        for json in [
+            r#"{"DepositMoney":{"amount":"200"}}"#,
+            r#"{"PlaceHold":{"holdId":"hold-1","amount":"12.5"}}"#,
+        ] {
+            let error = serde_json::from_str::<BankAccountCommand>(json).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("`amount` must be a JSON number"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    fn disputed() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 300.0,
+                balance: 300.0,
+            },
+            BankAccountEvent::DisputedFundsHeld {
+                dispute_id: "dispute-1".to_string(),
+                amount: 120.0,
+                placed_at: may_31(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_hold_disputed_funds() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 300.0,
+            balance: 300.0,
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(vec![previous])
+            .when(BankAccountCommand::HoldDisputedFunds {
+                dispute_id: "dispute-1".to_string(),
+                amount: 120.0,
+            })
+            .then_expect_events(vec![BankAccountEvent::DisputedFundsHeld {
+                dispute_id: "dispute-1".to_string(),
+                amount: 120.0,
+                placed_at: may_31(),
+            }]);
+
+        let mut account = BankAccount::default();
+        for event in disputed() {
+            account.apply(event);
+        }
+        assert_eq!(account.ledger_balance(), 300.0);
+        assert_eq!(account.available_balance(), 180.0);
+    }
+
+    #[test]
+    fn test_dispute_resolved_in_customer_favor() {
+        let expected = BankAccountEvent::DisputeResolved {
+            dispute_id: "dispute-1".to_string(),
+            in_customer_favor: true,
+            amount: 120.0,
+            balance: 300.0,
+            resolved_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(disputed())
+            .when(BankAccountCommand::ResolveDispute {
+                dispute_id: "dispute-1".to_string(),
+                in_customer_favor: true,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in disputed().into_iter().chain([expected]) {
+            account.apply(event);
+        }
+        assert_eq!(account.ledger_balance(), 300.0);
+        assert_eq!(account.available_balance(), 300.0);
+    }
+
+    #[test]
+    fn test_dispute_resolved_against_customer() {
+        let expected = BankAccountEvent::DisputeResolved {
+            dispute_id: "dispute-1".to_string(),
+            in_customer_favor: false,
+            amount: 120.0,
+            balance: 180.0,
+            resolved_at: may_31(),
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(disputed())
+            .when(BankAccountCommand::ResolveDispute {
+                dispute_id: "dispute-1".to_string(),
+                in_customer_favor: false,
+            })
+            .then_expect_events(vec![expected.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in disputed().into_iter().chain([expected]) {
+            account.apply(event);
+        }
+        assert_eq!(account.ledger_balance(), 180.0);
+        assert_eq!(account.available_balance(), 180.0);
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given_no_previous_events()
+            .when(BankAccountCommand::ResolveDispute {
+                dispute_id: "dispute-1".to_string(),
+                in_customer_favor: false,
+            })
+            .then_expect_error(BankAccountError("dispute not found".to_string()));
+    }
+
+    fn account_with_holds() -> BankAccount {
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 500.0,
+            balance: 500.0,
+        });
+        for hold_id in ["hold-c", "hold-a", "hold-d", "hold-b"] {
+            account.apply(BankAccountEvent::HoldPlaced {
+                hold_id: hold_id.to_string(),
+                amount: 10.0,
+                placed_at: may_31(),
+            });
+        }
+        account
+    }
+
+    #[test]
+    fn test_snapshot_serialization_is_deterministic() {
+        let first = serde_json::to_vec(&account_with_holds()).unwrap();
+        let second = serde_json::to_vec(&account_with_holds()).unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_snapshot_keys_are_sorted() {
+        let json = serde_json::to_string(&account_with_holds()).unwrap();
+
+        let positions: Vec<usize> = ["hold-a", "hold-b", "hold-c", "hold-d"]
+            .iter()
+            .map(|hold_id| json.find(&format!("\"{}\":", hold_id)).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    fn interest_bearing() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::InterestRateChanged { annual_rate: 0.05 },
+        ]
+    }
+
+    #[test]
+    fn test_accrual_skipped_while_interest_paused() {
+        let mut previous = interest_bearing();
+        previous.push(BankAccountEvent::InterestPaused);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::AccrueInterest { days: 73 })
+            .then_expect_events(vec![]);
+    }
+
+    #[test]
+    fn test_accrual_after_interest_resumed() {
+        let mut previous = interest_bearing();
+        previous.push(BankAccountEvent::InterestPaused);
+        previous.push(BankAccountEvent::InterestResumed);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::AccrueInterest { days: 73 })
+            .then_expect_events(vec![BankAccountEvent::InterestAccrued {
+                amount: 10.0,
+                balance: 1_010.0,
+            }]);
+    }
+
+    #[test]
+    fn test_withdraw_money_records_merchant_category() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+        let expected = vec![
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 40.0,
+                balance: 160.0,
+            },
+            BankAccountEvent::MerchantSpendRecorded {
+                mcc: "5411".to_string(),
+                amount: 40.0,
+            },
+        ];
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(40.0),
+                category: None,
+                mcc: Some("5411".to_string()),
+            })
+            .then_expect_events(expected)
+    }
+
+    #[test]
+    fn test_invalid_mcc_rejected() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+
+        for mcc in ["541", "54110", "54a1"] {
+            AccountTestFramework::with(BankAccountServices::default())
+                .given(vec![previous.clone()])
+                .when(BankAccountCommand::WriteCheck {
+                    check_number: "1170".to_string(),
+                    amount: 40.0,
+                    category: None,
+                    mcc: Some(mcc.to_string()),
+                })
+                .then_expect_error(BankAccountError("invalid mcc".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_open_account_with_holding_period() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given_no_previous_events()
+            .when(BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: Some(5),
+                branch_id: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: Date::from_ymd(2023, 6, 5),
+                branch_id: None,
+            }]);
+    }
+
+    /// An account funded with 200 whose first withdrawal is allowed on 2023-06-05.
+    fn in_holding_period() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: Date::from_ymd(2023, 6, 5),
+                branch_id: None,
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_holding_period_blocks_early_withdrawal() {
+        let previous = in_holding_period();
+        let withdraw = || BankAccountCommand::WithdrawMoney {
+            amount: positive(50.0),
+            category: None,
+            mcc: None,
+        };
+
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(previous.clone())
+            .when(withdraw())
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(previous.clone())
+            .when(BankAccountCommand::DepositMoney {
+                amount: positive(50.0),
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerDepositedMoney {
+                amount: 50.0,
+                balance: 250.0,
+            }]);
+
+        AccountTestFramework::with(services_on(2023, 6, 5))
+            .given(previous)
+            .when(withdraw())
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            }]);
+    }
+
+    #[test]
+    fn test_holding_period_blocks_early_check() {
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(in_holding_period())
+            .when(BankAccountCommand::WriteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+    }
+
+    #[test]
+    fn test_holding_period_blocks_early_charges_and_bill_payments() {
+        let commands = vec![
+            BankAccountCommand::AuthorizeCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 50.0,
+            },
+            BankAccountCommand::ScheduleBillPayment {
+                payee: "power-co".to_string(),
+                amount: 50.0,
+                due: Date::from_ymd(2023, 6, 10).unwrap(),
+            },
+        ];
+        for command in commands {
+            AccountTestFramework::with(services_on(2023, 6, 4))
+                .given(in_holding_period())
+                .when(command)
+                .then_expect_error(BankAccountError("holding period active".to_string()));
+        }
+
+        let mut previous = in_holding_period();
+        previous.push(BankAccountEvent::HoldPlaced {
+            hold_id: "auth-1".to_string(),
+            amount: 50.0,
+            placed_at: may_31(),
+        });
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(previous)
+            .when(BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 50.0,
+            })
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+    }
+
+    #[test]
+    fn test_holding_period_blocks_early_close_with_payout() {
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(in_holding_period())
+            .when(BankAccountCommand::CloseAccountWithPayout {
+                destination: "ext-123".to_string(),
+            })
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+    }
+
+    #[test]
+    fn test_holding_period_blocks_early_close_and_transfer() {
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(in_holding_period())
+            .when(BankAccountCommand::CloseAndTransfer {
+                to_account_id: "acct-2".to_string(),
+            })
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+    }
+
+    #[test]
+    fn test_events_of_type() {
+        let events = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            },
+            BankAccountEvent::InterestAccrued {
+                amount: 1.5,
+                balance: 151.5,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 25.0,
+                balance: 176.5,
+            },
+        ];
+
+        let deposits = events_of_type(&events, "CustomerDepositedMoney");
+
+        assert_eq!(deposits.len(), 2);
+        assert_eq!(deposits[1], &events[3]);
+        assert!(events_of_type(&events, "AccountClosed").is_empty());
+    }
+
+    #[test]
+    fn test_transfer_branch() {
+        let opened = BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds: None,
+            first_withdrawal_allowed_after: None,
+            branch_id: Some("branch-1".to_string()),
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::OpenAccount {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                holding_period_days: None,
+                branch_id: Some("branch-1".to_string()),
+            })
+            .then_expect_events(vec![opened.clone()]);
+
+        let transferred = BankAccountEvent::BranchTransferred {
+            old: Some("branch-1".to_string()),
+            new: "branch-2".to_string(),
+        };
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![opened.clone()])
+            .when(BankAccountCommand::TransferBranch {
+                new_branch_id: "branch-2".to_string(),
+            })
+            .then_expect_events(vec![transferred.clone()]);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![opened.clone()])
+            .when(BankAccountCommand::TransferBranch {
+                new_branch_id: "branch-1".to_string(),
+            })
+            .then_expect_error(BankAccountError("account already at branch".to_string()));
+
+        let mut account = BankAccount::default();
+        account.apply(opened);
+        account.apply(transferred);
+        assert_eq!(account.summarize().branch_id.as_deref(), Some("branch-2"));
+    }
+
+    #[test]
+    fn test_configure_sweep() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::ConfigureSweep {
+                target_balance: 1_000.0,
+                sweep_to: "invest-1".to_string(),
+            })
+            .then_expect_events(vec![BankAccountEvent::SweepConfigured {
+                target_balance: 1_000.0,
+                sweep_to: "invest-1".to_string(),
+            }]);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::ConfigureSweep {
+                target_balance: -1.0,
+                sweep_to: "invest-1".to_string(),
+            })
+            .then_expect_error(BankAccountError(
+                "sweep target must not be negative".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_sweep_commands() {
+        let today = Date::from_ymd(2023, 5, 31).unwrap();
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 1_500.0,
+            balance: 1_500.0,
+        });
+        assert!(account.sweep_commands(today).is_empty());
+
+        account.apply(BankAccountEvent::SweepConfigured {
+            target_balance: 1_000.0,
+            sweep_to: "invest-1".to_string(),
+        });
+        assert_eq!(
+            account.sweep_commands(today),
+            vec![BankAccountCommand::TransferMoney {
+                to_account_id: "invest-1".to_string(),
+                amount: 500.0,
+            }]
+        );
+
+        account.apply(BankAccountEvent::CustomerWithdrewCash {
+            amount: 600.0,
+            balance: 900.0,
+        });
+        assert!(account.sweep_commands(today).is_empty());
+    }
+
+    #[test]
+    fn test_transfer_money() {
+        let previous = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous.clone()])
+            .when(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 150.0,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerTransferredMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 150.0,
+                balance: 50.0,
+            }]);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous])
+            .when(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 250.0,
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 50".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_transfer_money_to_self() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![
+                BankAccountEvent::AccountOpened {
+                    account_id: "acct-1".to_string(),
+                    max_holds: None,
+                    first_withdrawal_allowed_after: None,
+                    branch_id: None,
+                },
+                BankAccountEvent::KycVerified {
+                    verification_id: "kyc-1".to_string(),
+                },
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 200.0,
+                    balance: 200.0,
+                },
+            ])
+            .when(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-1".to_string(),
+                amount: 150.0,
+            })
+            .then_expect_error(BankAccountError(
+                "cannot transfer to the same account".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_transfer_zero_amount() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            }])
+            .when(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 0.0,
+            })
+            .then_expect_error(BankAccountError(
+                "transfer amount must be positive".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_transfer_nan_amount() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            }])
+            .when(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: f64::NAN,
+            })
+            .then_expect_error(BankAccountError(
+                "transfer amount must be positive".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_balance_snapshot_is_a_replay_starting_point() {
+        let snapshot = BankAccountEvent::BalanceSnapshotted {
+            balance: 150.0,
+            as_of: Date::from_ymd(2023, 5, 31).unwrap().start_of_day(),
+        };
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 200.0,
+                    balance: 200.0,
+                },
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 50.0,
+                    balance: 150.0,
+                },
+            ])
+            .when(BankAccountCommand::RecordBalanceSnapshot)
+            .then_expect_events(vec![snapshot.clone()]);
+
+        let withdrawal = BankAccountEvent::CustomerWithdrewCash {
+            amount: 25.0,
+            balance: 125.0,
+        };
+        let mut full = BankAccount::default();
+        for event in [
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            },
+            snapshot.clone(),
+        ] {
+            full.apply(event);
         }
+        assert_eq!(full.ledger_balance(), 150.0);
+        full.apply(withdrawal.clone());
+
+        let mut from_snapshot = BankAccount::default();
+        from_snapshot.apply(snapshot);
+        from_snapshot.apply(withdrawal);
+        assert_eq!(from_snapshot.ledger_balance(), full.ledger_balance());
     }
 
-    fn apply(&mut self, event: Self::Event) {
-        match event {
-            BankAccountEvent::AccountOpened { .. } => self.opened = true,
-            BankAccountEvent::CustomerDepositedMoney { amount: _, balance } => {
-                self.balance = balance
-            }
-            BankAccountEvent::CustomerWithdrewCash { amount: _, balance } => self.balance = balance,
+    fn may_31_transactions() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 700.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 650.0,
+            },
             BankAccountEvent::CustomerWroteCheck {
-                check_number: _,
-                amount: _,
-                balance,
-            } => self.balance = balance,
+                check_number: "1170".to_string(),
+                amount: 100.0,
+                balance: 550.0,
+            },
+            BankAccountEvent::WireReceived {
+                reference: "FED-1".to_string(),
+                amount: 300.0,
+                sender: "Acme Corp".to_string(),
+                balance: 850.0,
+            },
+        ]
+    }
+
+    /// The ledger for `may_31_transactions`: the opening deposit on May 30, the rest on
+    /// May 31.
+    fn may_31_services() -> BankAccountServices {
+        let ledger = LedgerQuery::default();
+        ledger.record("acct-1", 3, Date::from_ymd(2023, 5, 30).unwrap(), 500.0);
+        for (sequence, amount) in [(4, 200.0), (5, -50.0), (6, -100.0), (7, 300.0)] {
+            ledger.record(
+                "acct-1",
+                sequence,
+                Date::from_ymd(2023, 5, 31).unwrap(),
+                amount,
+            );
         }
+        services_on(2023, 5, 31).with_ledger(ledger)
     }
-}
 
-fn main() {
-    println!("Hello, world!");
-}
+    #[test]
+    fn test_void_day() {
+        let date = Date::from_ymd(2023, 5, 31).unwrap();
+        let expected = vec![
+            BankAccountEvent::TransactionReversed {
+                original_sequence: 7,
+                amount: -300.0,
+                balance: 550.0,
+            },
+            BankAccountEvent::TransactionReversed {
+                original_sequence: 6,
+                amount: 100.0,
+                balance: 650.0,
+            },
+            BankAccountEvent::TransactionReversed {
+                original_sequence: 5,
+                amount: 50.0,
+                balance: 700.0,
+            },
+            BankAccountEvent::TransactionReversed {
+                original_sequence: 4,
+                amount: -200.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::DayVoided { date },
+        ];
 
-#[cfg(test)]
-mod aggregate_tests {
-    use super::*;
-    use cqrs_es::test::TestFramework;
+        AccountTestFramework::with(may_31_services())
+            .given(may_31_transactions())
+            .when(BankAccountCommand::VoidDay { date })
+            .then_expect_events(expected.clone());
 
-    type AccountTestFramework = TestFramework<BankAccount>;
+        let mut account = BankAccount::default();
+        for event in may_31_transactions().into_iter().chain(expected) {
+            account.apply(event);
+        }
+        assert_eq!(account.ledger_balance(), 500.0);
+    }
 
     #[test]
-    fn test_deposit_money() {
-        let expected = BankAccountEvent::CustomerDepositedMoney {
+    fn test_void_day_without_transactions() {
+        AccountTestFramework::with(may_31_services())
+            .given(may_31_transactions())
+            .when(BankAccountCommand::VoidDay {
+                date: Date::from_ymd(2023, 5, 29).unwrap(),
+            })
+            .then_expect_error(BankAccountError("no transactions to void".to_string()));
+    }
+
+    #[test]
+    fn test_void_day_rejects_spent_deposit() {
+        let previous = vec![
+            opened(),
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 100.0,
+                balance: 100.0,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 100.0,
+                balance: 0.0,
+            },
+        ];
+        let ledger = LedgerQuery::default();
+        ledger.record("acct-1", 3, Date::from_ymd(2023, 5, 1).unwrap(), 100.0);
+        ledger.record("acct-1", 4, Date::from_ymd(2023, 5, 2).unwrap(), -100.0);
+
+        AccountTestFramework::with(services_on(2023, 5, 2).with_ledger(ledger))
+            .given(previous)
+            .when(BankAccountCommand::VoidDay {
+                date: Date::from_ymd(2023, 5, 1).unwrap(),
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 100".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_void_day_rejects_transaction_missing_from_stream() {
+        let services = may_31_services();
+        services
+            .ledger
+            .record("acct-1", 9, Date::from_ymd(2023, 5, 31).unwrap(), 25.0);
+
+        AccountTestFramework::with(services)
+            .given(may_31_transactions())
+            .when(BankAccountCommand::VoidDay {
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            })
+            .then_expect_error(BankAccountError("transaction not found: 9".to_string()));
+    }
+
+    #[test]
+    fn test_void_day_rejects_already_reversed_transaction() {
+        let mut previous = may_31_transactions();
+        previous.push(BankAccountEvent::TransactionReversed {
+            original_sequence: 5,
+            amount: 50.0,
+            balance: 900.0,
+        });
+
+        AccountTestFramework::with(may_31_services())
+            .given(previous)
+            .when(BankAccountCommand::VoidDay {
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            })
+            .then_expect_error(BankAccountError(
+                "transaction already reversed: 5".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_earn_and_redeem_points() {
+        let deposited = BankAccountEvent::CustomerDepositedMoney {
             amount: 200.0,
             balance: 200.0,
         };
+        let earned = BankAccountEvent::PointsEarned {
+            points: 500,
+            reason: "signup bonus".to_string(),
+            total: 500,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposited.clone()])
+            .when(BankAccountCommand::EarnPoints {
+                points: 500,
+                reason: "signup bonus".to_string(),
+            })
+            .then_expect_events(vec![earned.clone()]);
+
+        let redeemed = BankAccountEvent::PointsRedeemed {
+            points: 200,
+            total: 300,
+        };
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposited.clone(), earned.clone()])
+            .when(BankAccountCommand::RedeemPoints { points: 200 })
+            .then_expect_events(vec![redeemed.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in [deposited, earned, redeemed] {
+            account.apply(event);
+        }
+        assert_eq!(account.points(), 300);
+        assert_eq!(account.ledger_balance(), 200.0);
+    }
+
+    #[test]
+    fn test_redeem_more_points_than_earned() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::PointsEarned {
+                points: 100,
+                reason: "signup bonus".to_string(),
+                total: 100,
+            }])
+            .when(BankAccountCommand::RedeemPoints { points: 101 })
+            .then_expect_error(BankAccountError("insufficient points".to_string()));
+    }
 
-        AccountTestFramework::with(BankAccountServices)
+    #[test]
+    fn test_shortfall_for() {
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 100.0,
+            balance: 100.0,
+        });
+
+        assert_eq!(account.shortfall_for(60.0), 0.0);
+        assert_eq!(account.shortfall_for(150.0), 50.0);
+
+        // Held funds are not available, so they add to the shortfall.
+        account.apply(BankAccountEvent::HoldPlaced {
+            hold_id: "hold-1".to_string(),
+            amount: 30.0,
+            placed_at: may_31(),
+        });
+        assert_eq!(account.shortfall_for(150.0), 80.0);
+    }
+
+    fn flagged() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 5_000.0,
+                balance: 5_000.0,
+            },
+            BankAccountEvent::FlaggedForReview {
+                reason: "unusual velocity".to_string(),
+                severity: ReviewSeverity::High,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_flag_for_review() {
+        AccountTestFramework::with(BankAccountServices::default())
             .given_no_previous_events()
-            .when(BankAccountCommand::DepositMoney { amount: 200.0 })
-            .then_expect_events(vec![expected]);
+            .when(BankAccountCommand::FlagForReview {
+                reason: "unusual velocity".to_string(),
+                severity: ReviewSeverity::High,
+            })
+            .then_expect_events(vec![BankAccountEvent::FlaggedForReview {
+                reason: "unusual velocity".to_string(),
+                severity: ReviewSeverity::High,
+            }]);
+
+        let mut account = BankAccount::default();
+        flagged().into_iter().for_each(|event| account.apply(event));
+        assert_eq!(account.review_reason(), Some("unusual velocity"));
     }
 
     #[test]
-    fn test_deposit_money_with_balance() {
+    fn test_review_blocks_large_withdrawals() {
+        let services = || BankAccountServices::default().with_review_withdrawal_limit(500.0);
+        let withdraw = |amount| BankAccountCommand::WithdrawMoney {
+            amount: positive(amount),
+            category: None,
+            mcc: None,
+        };
+
+        AccountTestFramework::with(services())
+            .given(flagged())
+            .when(withdraw(600.0))
+            .then_expect_error(BankAccountError("account under review".to_string()));
+
+        AccountTestFramework::with(services())
+            .given(flagged())
+            .when(withdraw(400.0))
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 400.0,
+                balance: 4_600.0,
+            }]);
+    }
+
+    #[test]
+    fn test_review_blocks_closing_payout_and_large_captures() {
+        let services = || BankAccountServices::default().with_review_withdrawal_limit(500.0);
+
+        AccountTestFramework::with(services())
+            .given(flagged())
+            .when(BankAccountCommand::CloseAccountWithPayout {
+                destination: "021000021-998877".to_string(),
+            })
+            .then_expect_error(BankAccountError("account under review".to_string()));
+
+        AccountTestFramework::with(services())
+            .given(flagged())
+            .when(BankAccountCommand::CloseAndTransfer {
+                to_account_id: "ACCT-2".to_string(),
+            })
+            .then_expect_error(BankAccountError("account under review".to_string()));
+
+        let mut authorized = flagged();
+        authorized.push(BankAccountEvent::HoldPlaced {
+            hold_id: "auth-1".to_string(),
+            amount: 800.0,
+            placed_at: may_31(),
+        });
+        AccountTestFramework::with(services())
+            .given(authorized)
+            .when(BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 800.0,
+            })
+            .then_expect_error(BankAccountError("account under review".to_string()));
+
+        let mut scheduled = flagged();
+        scheduled.push(BankAccountEvent::BillPaymentScheduled {
+            payee: "power-co".to_string(),
+            amount: 800.0,
+            due: Date::from_ymd(2023, 6, 1).unwrap(),
+        });
+        AccountTestFramework::with(services())
+            .given(scheduled)
+            .when(BankAccountCommand::CaptureBillPayment {
+                payee: "power-co".to_string(),
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            })
+            .then_expect_error(BankAccountError("account under review".to_string()));
+    }
+
+    #[test]
+    fn test_clear_review() {
+        let mut previous = flagged();
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous.clone())
+            .when(BankAccountCommand::ClearReview)
+            .then_expect_events(vec![BankAccountEvent::ReviewCleared]);
+
+        previous.push(BankAccountEvent::ReviewCleared);
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous)
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(2_000.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 2_000.0,
+                balance: 3_000.0,
+            }]);
+    }
+
+    /// A small deterministic xorshift generator, so failures replay identically.
+    struct CommandGenerator(u64);
+
+    impl CommandGenerator {
+        fn next(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+
+        fn amount(&mut self) -> f64 {
+            (self.next(50_000) + 1) as f64 / 100.0
+        }
+
+        /// An amount for commands taking a raw `f64`, sometimes zero or negative so that
+        /// handlers missing a positivity check break the invariants below.
+        fn raw_amount(&mut self) -> f64 {
+            match self.next(8) {
+                0 => 0.0,
+                1 => -self.amount(),
+                _ => self.amount(),
+            }
+        }
+
+        fn command(&mut self, step: u64) -> BankAccountCommand {
+            let earlier = self.next(step.max(1));
+            match self.next(17) {
+                0 | 1 => BankAccountCommand::DepositMoney {
+                    amount: positive(self.amount()),
+                },
+                2 => BankAccountCommand::WithdrawMoney {
+                    amount: positive(self.amount()),
+                    category: None,
+                    mcc: None,
+                },
+                3 => BankAccountCommand::WriteCheck {
+                    check_number: step.to_string(),
+                    amount: self.raw_amount(),
+                    category: None,
+                    mcc: None,
+                },
+                4 => BankAccountCommand::TransferMoney {
+                    to_account_id: "acct-2".to_string(),
+                    amount: self.raw_amount(),
+                },
+                5 => BankAccountCommand::AuthorizeCharge {
+                    auth_id: format!("auth-{}", step),
+                    amount: self.raw_amount(),
+                },
+                6 => BankAccountCommand::CaptureCharge {
+                    auth_id: format!("auth-{}", earlier),
+                    amount: self.raw_amount(),
+                },
+                7 => BankAccountCommand::ReleaseHold {
+                    hold_id: format!("auth-{}", earlier),
+                },
+                8 => BankAccountCommand::ScheduleBillPayment {
+                    payee: format!("payee-{}", step),
+                    amount: self.raw_amount(),
+                    due: Date::from_ymd(2023, 5, 31).unwrap(),
+                },
+                9 => BankAccountCommand::CaptureBillPayment {
+                    payee: format!("payee-{}", earlier),
+                    due: Date::from_ymd(2023, 5, 31).unwrap(),
+                },
+                10 => BankAccountCommand::HoldDisputedFunds {
+                    dispute_id: format!("dispute-{}", step),
+                    amount: self.raw_amount(),
+                },
+                11 => BankAccountCommand::ResolveDispute {
+                    dispute_id: format!("dispute-{}", earlier),
+                    in_customer_favor: false,
+                },
+                12 => BankAccountCommand::ReceiveWire {
+                    reference: format!("wire-{}", step),
+                    amount: self.raw_amount(),
+                    sender: "Acme Corp".to_string(),
+                },
+                13 => BankAccountCommand::ApplyBonus {
+                    bonus_id: format!("bonus-{}", step),
+                    amount: self.raw_amount(),
+                    min_balance: 0.0,
+                },
+                14 => BankAccountCommand::PlaceHold {
+                    hold_id: format!("auth-{}", step),
+                    amount: self.raw_amount(),
+                },
+                15 => BankAccountCommand::AbsorbAccount {
+                    from_account_id: format!("acct-{}", step + 10),
+                    amount: self.raw_amount(),
+                },
+                _ => BankAccountCommand::DepositPaycheck {
+                    amount: self.raw_amount(),
+                    rules: vec![("savings".to_string(), 100.0)],
+                },
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_balance_never_negative() {
+        let services = services_on(2023, 5, 31);
+        for seed in 1..=20_u64 {
+            let mut generator = CommandGenerator(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let mut account = BankAccount::default();
+            account.apply(BankAccountEvent::RoundUpChanged {
+                enabled: seed % 2 == 0,
+            });
+
+            for step in 0..200 {
+                let command = generator.command(step);
+                let description = format!("{:?}", command);
+                let Ok(events) = account.handle(command, &services).await else {
+                    continue;
+                };
+                for event in events {
+                    account.apply(event);
+                }
+                assert!(
+                    account.ledger_balance() > -FUNDS_EPSILON,
+                    "seed {} step {}: {} left balance {}",
+                    seed,
+                    step,
+                    description,
+                    account.ledger_balance()
+                );
+                // Holds only ever reserve funds, so they can't make more available.
+                assert!(
+                    account.available_balance() <= account.ledger_balance() + FUNDS_EPSILON,
+                    "seed {} step {}: {} left available {} above balance {}",
+                    seed,
+                    step,
+                    description,
+                    account.available_balance(),
+                    account.ledger_balance()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_withhold_tax_on_interest() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 1_000.0,
+                balance: 1_000.0,
+            },
+            BankAccountEvent::InterestAccrued {
+                amount: 40.0,
+                balance: 1_040.0,
+            },
+        ];
+        let withheld = BankAccountEvent::TaxWithheld {
+            amount: 10.0,
+            balance: 1_030.0,
+            tax_year: 2023,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous.clone())
+            .when(BankAccountCommand::WithholdTax {
+                amount: 10.0,
+                tax_year: 2023,
+            })
+            .then_expect_events(vec![withheld.clone()]);
+
+        let mut account = BankAccount::default();
+        let withheld_again = BankAccountEvent::TaxWithheld {
+            amount: 10.0,
+            balance: 1_020.0,
+            tax_year: 2023,
+        };
+        for event in previous.into_iter().chain([withheld, withheld_again]) {
+            account.apply(event);
+        }
+        assert_eq!(account.tax_withheld_for(2023), 20.0);
+        assert_eq!(account.tax_withheld_for(2022), 0.0);
+        assert_eq!(account.ledger_balance(), 1_020.0);
+    }
+
+    #[test]
+    fn test_withhold_more_than_balance() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::InterestAccrued {
+                amount: 40.0,
+                balance: 40.0,
+            }])
+            .when(BankAccountCommand::WithholdTax {
+                amount: 50.0,
+                tax_year: 2023,
+            })
+            .then_expect_error(BankAccountError("withholding exceeds balance".to_string()));
+    }
+
+    #[test]
+    fn test_redenominate() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 250_000.0,
+                balance: 250_000.0,
+            },
+            BankAccountEvent::PaycheckAllocated {
+                bucket: "savings".to_string(),
+                amount: 50_000.0,
+            },
+        ];
+        let redenominated = BankAccountEvent::Redenominated {
+            old_currency: None,
+            new_currency: "NEW".to_string(),
+            ratio: 0.001,
+            balance: 250.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(previous.clone())
+            .when(BankAccountCommand::Redenominate {
+                new_currency: "NEW".to_string(),
+                ratio: 0.001,
+            })
+            .then_expect_events(vec![redenominated.clone()]);
+
+        let mut account = BankAccount::default();
+        for event in previous.into_iter().chain([redenominated]) {
+            account.apply(event);
+        }
+        assert_eq!(account.ledger_balance(), 250.0);
+        assert_eq!(account.bucket_balance("savings"), 50.0);
+        assert_eq!(account.unallocated_balance(), 200.0);
+    }
+
+    #[test]
+    fn test_redenominate_converts_limits_and_schedules() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 250_000.0,
+                balance: 250_000.0,
+            },
+            BankAccountEvent::TransactionLimitSet {
+                max_transaction: 100_000.0,
+            },
+            BankAccountEvent::BillPaymentScheduled {
+                payee: "power-co".to_string(),
+                amount: 20_000.0,
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+            BankAccountEvent::RecurringDepositScheduled {
+                schedule_id: "auto-save".to_string(),
+                amount: 5_000.0,
+                interval_days: 7,
+                next_run: Date::from_ymd(2023, 6, 7).unwrap(),
+            },
+        ];
+        let mut account = BankAccount::default();
+        for event in previous {
+            account.apply(event);
+        }
+        account.apply(BankAccountEvent::Redenominated {
+            old_currency: None,
+            new_currency: "NEW".to_string(),
+            ratio: 0.001,
+            balance: 250.0,
+        });
+        assert_eq!(account.max_transaction, 100.0);
+        assert_eq!(account.scheduled_payments[0].amount, 20.0);
+        assert_eq!(account.recurring_deposits["auto-save"].amount, 5.0);
+        assert_eq!(account.available_balance(), 230.0);
+    }
+
+    #[test]
+    fn test_redenominate_rejects_zero_ratio() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::Redenominate {
+                new_currency: "NEW".to_string(),
+                ratio: 0.0,
+            })
+            .then_expect_error(BankAccountError(
+                "redenomination ratio must be positive".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_spin_off() {
         let previous = BankAccountEvent::CustomerDepositedMoney {
-            amount: 200.0,
-            balance: 200.0,
+            amount: 500.0,
+            balance: 500.0,
         };
-        let expected = BankAccountEvent::CustomerDepositedMoney {
+        let spun_off = BankAccountEvent::AccountSpunOff {
+            new_account_id: "acct-2".to_string(),
             amount: 200.0,
-            balance: 400.0,
+            balance: 300.0,
         };
 
-        AccountTestFramework::with(BankAccountServices)
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![previous.clone()])
+            .when(BankAccountCommand::SpinOff {
+                new_account_id: "acct-2".to_string(),
+                amount: 200.0,
+            })
+            .then_expect_events(vec![spun_off.clone()]);
+
+        AccountTestFramework::with(BankAccountServices::default())
             .given(vec![previous])
-            .when(BankAccountCommand::DepositMoney { amount: 200.0 })
-            .then_expect_events(vec![expected])
+            .when(BankAccountCommand::SpinOff {
+                new_account_id: "acct-2".to_string(),
+                amount: 600.0,
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 100".to_string(),
+            ));
+
+        let saga = spin_off_saga("acct-1", &spun_off);
+        assert_eq!(saga.len(), 2);
+        assert_eq!(
+            saga[1],
+            (
+                "acct-2".to_string(),
+                BankAccountCommand::AbsorbAccount {
+                    from_account_id: "acct-1".to_string(),
+                    amount: 200.0,
+                }
+            )
+        );
     }
 
     #[test]
-    fn test_withdraw_money() {
-        let previous = BankAccountEvent::CustomerDepositedMoney {
-            amount: 200.0,
-            balance: 200.0,
+    fn test_spin_off_requires_new_account_id() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            }])
+            .when(BankAccountCommand::SpinOff {
+                new_account_id: String::new(),
+                amount: 200.0,
+            })
+            .then_expect_error(BankAccountError("invalid spin-off account".to_string()));
+    }
+
+    #[test]
+    fn test_spin_off_guards_match_withdrawals() {
+        let spin_off = |amount| BankAccountCommand::SpinOff {
+            new_account_id: "acct-2".to_string(),
+            amount,
         };
-        let expected = BankAccountEvent::CustomerWithdrewCash {
-            amount: 100.0,
-            balance: 100.0,
+
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(vec![
+                BankAccountEvent::AccountOpened {
+                    account_id: "acct-1".to_string(),
+                    max_holds: None,
+                    first_withdrawal_allowed_after: Date::from_ymd(2023, 6, 5),
+                    branch_id: None,
+                },
+                BankAccountEvent::KycVerified {
+                    verification_id: "kyc-1".to_string(),
+                },
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+            ])
+            .when(spin_off(200.0))
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+                BankAccountEvent::TransactionLimitSet {
+                    max_transaction: 100.0,
+                },
+            ])
+            .when(spin_off(200.0))
+            .then_expect_error(BankAccountError(
+                "transaction too large, limit: 100".to_string(),
+            ));
+
+        AccountTestFramework::with(
+            BankAccountServices::default().with_review_withdrawal_limit(500.0),
+        )
+        .given(flagged())
+        .when(spin_off(600.0))
+        .then_expect_error(BankAccountError("account under review".to_string()));
+    }
+
+    #[test]
+    fn test_transfer_blocked_during_holding_period() {
+        AccountTestFramework::with(services_on(2023, 6, 4))
+            .given(vec![
+                BankAccountEvent::AccountOpened {
+                    account_id: "acct-1".to_string(),
+                    max_holds: None,
+                    first_withdrawal_allowed_after: Date::from_ymd(2023, 6, 5),
+                    branch_id: None,
+                },
+                BankAccountEvent::KycVerified {
+                    verification_id: "kyc-1".to_string(),
+                },
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+            ])
+            .when(BankAccountCommand::TransferMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: 200.0,
+            })
+            .then_expect_error(BankAccountError("holding period active".to_string()));
+    }
+
+    #[test]
+    fn test_teller_transactions() {
+        let deposited = BankAccountEvent::TellerDeposited {
+            teller_id: "teller-7".to_string(),
+            amount: 300.0,
+            balance: 300.0,
         };
 
-        AccountTestFramework::with(BankAccountServices)
-            .given(vec![previous])
-            .when(BankAccountCommand::WithdrawMoney { amount: 100.0 })
-            .then_expect_events(vec![expected])
+        AccountTestFramework::with(BankAccountServices::default())
+            .given_no_previous_events()
+            .when(BankAccountCommand::TellerDeposit {
+                teller_id: "teller-7".to_string(),
+                amount: positive(300.0),
+            })
+            .then_expect_events(vec![deposited.clone()]);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposited.clone()])
+            .when(BankAccountCommand::TellerWithdrawal {
+                teller_id: "teller-7".to_string(),
+                amount: positive(120.0),
+            })
+            .then_expect_events(vec![BankAccountEvent::TellerWithdrew {
+                teller_id: "teller-7".to_string(),
+                amount: 120.0,
+                balance: 180.0,
+            }]);
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposited])
+            .when(BankAccountCommand::TellerWithdrawal {
+                teller_id: "teller-7".to_string(),
+                amount: positive(400.0),
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 100".to_string(),
+            ));
+    }
+
+    fn garnished() -> Vec<BankAccountEvent> {
+        vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::GarnishmentPlaced {
+                case_number: "CV-2023-118".to_string(),
+                amount: 300.0,
+                placed_at: may_31(),
+            },
+        ]
     }
 
     #[test]
-    fn test_withdraw_money_funds_unavailable() {
-        AccountTestFramework::with(BankAccountServices)
+    fn test_place_garnishment() {
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+                BankAccountEvent::HoldPlaced {
+                    hold_id: "auth-1".to_string(),
+                    amount: 400.0,
+                    placed_at: may_31(),
+                },
+            ])
+            .when(BankAccountCommand::PlaceGarnishment {
+                case_number: "CV-2023-118".to_string(),
+                amount: 300.0,
+            })
+            .then_expect_events(vec![BankAccountEvent::GarnishmentPlaced {
+                case_number: "CV-2023-118".to_string(),
+                amount: 300.0,
+                placed_at: may_31(),
+            }]);
+
+        let mut account = BankAccount::default();
+        garnished()
+            .into_iter()
+            .for_each(|event| account.apply(event));
+        assert_eq!(account.garnished(), 300.0);
+        assert_eq!(account.available_balance(), 200.0);
+    }
+
+    #[test]
+    fn test_garnishment_blocks_encroaching_withdrawal() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(garnished())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(250.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_error(BankAccountError(
+                "funds not available, shortfall: 50".to_string(),
+            ));
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(garnished())
+            .when(BankAccountCommand::WithdrawMoney {
+                amount: positive(200.0),
+                category: None,
+                mcc: None,
+            })
+            .then_expect_events(vec![BankAccountEvent::CustomerWithdrewCash {
+                amount: 200.0,
+                balance: 300.0,
+            }]);
+    }
+
+    #[test]
+    fn test_garnishment_blocks_close() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(garnished())
+            .when(BankAccountCommand::CloseAccountWithPayout {
+                destination: "021000021-998877".to_string(),
+            })
+            .then_expect_error(BankAccountError(
+                "account has active garnishments".to_string(),
+            ));
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(garnished())
+            .when(BankAccountCommand::CloseAndTransfer {
+                to_account_id: "ACCT-2".to_string(),
+            })
+            .then_expect_error(BankAccountError(
+                "account has active garnishments".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_garnishment_blocks_capture_of_held_funds() {
+        let authorized = BankAccountEvent::HoldPlaced {
+            hold_id: "auth-1".to_string(),
+            amount: 400.0,
+            placed_at: may_31(),
+        };
+        let scheduled = BankAccountEvent::BillPaymentScheduled {
+            payee: "power-co".to_string(),
+            amount: 400.0,
+            due: Date::from_ymd(2023, 6, 1).unwrap(),
+        };
+        let garnishment = BankAccountEvent::GarnishmentPlaced {
+            case_number: "CV-2023-118".to_string(),
+            amount: 300.0,
+            placed_at: may_31(),
+        };
+        let deposited = BankAccountEvent::CustomerDepositedMoney {
+            amount: 500.0,
+            balance: 500.0,
+        };
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposited.clone(), authorized, garnishment.clone()])
+            .when(BankAccountCommand::CaptureCharge {
+                auth_id: "auth-1".to_string(),
+                amount: 400.0,
+            })
+            .then_expect_error(BankAccountError("funds garnished".to_string()));
+
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![deposited, scheduled, garnishment])
+            .when(BankAccountCommand::CaptureBillPayment {
+                payee: "power-co".to_string(),
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            })
+            .then_expect_error(BankAccountError("funds garnished".to_string()));
+    }
+
+    #[test]
+    fn test_garnishment_blocks_lost_dispute() {
+        let previous = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::DisputedFundsHeld {
+                dispute_id: "dispute-1".to_string(),
+                amount: 400.0,
+                placed_at: may_31(),
+            },
+            BankAccountEvent::GarnishmentPlaced {
+                case_number: "CV-2023-118".to_string(),
+                amount: 300.0,
+                placed_at: may_31(),
+            },
+        ];
+        let resolve = |in_customer_favor| BankAccountCommand::ResolveDispute {
+            dispute_id: "dispute-1".to_string(),
+            in_customer_favor,
+        };
+
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous.clone())
+            .when(resolve(false))
+            .then_expect_error(BankAccountError("funds garnished".to_string()));
+
+        // Releasing the funds to the customer moves no money, so the garnishment allows it.
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(previous)
+            .when(resolve(true))
+            .then_expect_events(vec![BankAccountEvent::DisputeResolved {
+                dispute_id: "dispute-1".to_string(),
+                in_customer_favor: true,
+                amount: 400.0,
+                balance: 500.0,
+                resolved_at: may_31(),
+            }]);
+    }
+
+    #[test]
+    fn test_garnishment_blocks_withholding() {
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(garnished())
+            .when(BankAccountCommand::WithholdTax {
+                amount: 250.0,
+                tax_year: 2023,
+            })
+            .then_expect_error(BankAccountError("withholding exceeds balance".to_string()));
+    }
+
+    #[test]
+    fn test_release_garnishment() {
+        let released = BankAccountEvent::GarnishmentReleased {
+            case_number: "CV-2023-118".to_string(),
+            released_at: may_31(),
+        };
+        AccountTestFramework::with(services_on(2023, 5, 31))
+            .given(garnished())
+            .when(BankAccountCommand::ReleaseGarnishment {
+                case_number: "CV-2023-118".to_string(),
+            })
+            .then_expect_events(vec![released.clone()]);
+
+        let mut account = BankAccount::default();
+        garnished()
+            .into_iter()
+            .for_each(|event| account.apply(event));
+        account.apply(released);
+        assert_eq!(account.available_balance(), 500.0);
+    }
+
+    fn june(day: u32) -> Date {
+        Date::from_ymd(2023, 6, day).unwrap()
+    }
+
+    fn auto_save_scheduled() -> BankAccountEvent {
+        BankAccountEvent::RecurringDepositScheduled {
+            schedule_id: "auto-save".to_string(),
+            amount: 25.0,
+            interval_days: 7,
+            next_run: june(7),
+        }
+    }
+
+    #[test]
+    fn test_schedule_recurring_deposit() {
+        AccountTestFramework::with(services_on(2023, 6, 1))
             .given_no_previous_events()
-            .when(BankAccountCommand::WithdrawMoney { amount: 200.0 })
-            .then_expect_error(BankAccountError("funds not available".to_string()))
+            .when(BankAccountCommand::ScheduleRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+                amount: positive(25.0),
+                interval_days: 7,
+                next_run: june(7),
+            })
+            .then_expect_events(vec![auto_save_scheduled()]);
+
+        AccountTestFramework::with(services_on(2023, 6, 1))
+            .given_no_previous_events()
+            .when(BankAccountCommand::ScheduleRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+                amount: positive(25.0),
+                interval_days: 0,
+                next_run: june(7),
+            })
+            .then_expect_error(BankAccountError(
+                "interval must be at least one day".to_string(),
+            ));
+    }
+
+    #[test]
+    fn test_due_recurring_deposits() {
+        let mut account = BankAccount::default();
+        account.apply(auto_save_scheduled());
+
+        assert!(account.due_recurring_deposits(june(6)).is_empty());
+        let run = BankAccountCommand::RunRecurringDeposit {
+            schedule_id: "auto-save".to_string(),
+            due: june(7),
+        };
+        assert_eq!(account.due_recurring_deposits(june(7)), vec![run]);
+        assert_eq!(account.due_recurring_deposits(june(9)).len(), 1);
+
+        AccountTestFramework::with(services_on(2023, 6, 7))
+            .given(vec![auto_save_scheduled()])
+            .when(BankAccountCommand::RunRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+                due: june(7),
+            })
+            .then_expect_events(vec![
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 25.0,
+                    balance: 25.0,
+                },
+                BankAccountEvent::RecurringDepositAdvanced {
+                    schedule_id: "auto-save".to_string(),
+                    next_run: june(14),
+                },
+            ]);
+
+        account.apply(BankAccountEvent::RecurringDepositAdvanced {
+            schedule_id: "auto-save".to_string(),
+            next_run: june(14),
+        });
+        assert!(account.due_recurring_deposits(june(9)).is_empty());
+        assert_eq!(account.due_recurring_deposits(june(14)).len(), 1);
+    }
+
+    #[test]
+    fn test_cancel_recurring_deposit() {
+        let cancelled = BankAccountEvent::RecurringDepositCancelled {
+            schedule_id: "auto-save".to_string(),
+        };
+        AccountTestFramework::with(BankAccountServices::default())
+            .given(vec![auto_save_scheduled()])
+            .when(BankAccountCommand::CancelRecurringDeposit {
+                schedule_id: "auto-save".to_string(),
+            })
+            .then_expect_events(vec![cancelled.clone()]);
+
+        let mut account = BankAccount::default();
+        account.apply(auto_save_scheduled());
+        account.apply(cancelled);
+        assert!(account.due_recurring_deposits(june(7)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_raw_amount_commands_reject_non_positive_amounts() {
+        let services = services_on(2023, 5, 31);
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 100.0,
+            balance: 100.0,
+        });
+        account.apply(BankAccountEvent::HoldPlaced {
+            hold_id: "auth-1".to_string(),
+            amount: 50.0,
+            placed_at: may_31(),
+        });
+
+        for amount in [-500.0, 0.0] {
+            let commands = vec![
+                BankAccountCommand::WriteCheck {
+                    check_number: "1170".to_string(),
+                    amount,
+                    category: None,
+                    mcc: None,
+                },
+                BankAccountCommand::ReceiveWire {
+                    reference: "FED-1".to_string(),
+                    amount,
+                    sender: "Acme Corp".to_string(),
+                },
+                BankAccountCommand::ApplyBonus {
+                    bonus_id: "promo".to_string(),
+                    amount,
+                    min_balance: 0.0,
+                },
+                BankAccountCommand::PlaceHold {
+                    hold_id: "hold-2".to_string(),
+                    amount,
+                },
+                BankAccountCommand::AuthorizeCharge {
+                    auth_id: "auth-2".to_string(),
+                    amount,
+                },
+                BankAccountCommand::CaptureCharge {
+                    auth_id: "auth-1".to_string(),
+                    amount,
+                },
+                BankAccountCommand::AbsorbAccount {
+                    from_account_id: "acct-3".to_string(),
+                    amount,
+                },
+                BankAccountCommand::DepositPaycheck {
+                    amount,
+                    rules: vec![("savings".to_string(), 100.0)],
+                },
+                BankAccountCommand::ScheduleBillPayment {
+                    payee: "power-co".to_string(),
+                    amount,
+                    due: Date::from_ymd(2023, 6, 1).unwrap(),
+                },
+                BankAccountCommand::HoldDisputedFunds {
+                    dispute_id: "dispute-1".to_string(),
+                    amount,
+                },
+            ];
+            for command in commands {
+                let command_type = command.command_type();
+                let result = account.handle(command, &services).await;
+                assert_eq!(
+                    result,
+                    Err(BankAccountError(format!(
+                        "amount must be positive: {}",
+                        amount
+                    ))),
+                    "{}",
+                    command_type
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kyc_gates_every_balance_change() {
+        let services = services_on(2023, 5, 31);
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds: None,
+            first_withdrawal_allowed_after: None,
+            branch_id: None,
+        });
+        for command in one_of_each_command() {
+            let command_type = command.command_type();
+            let moves_money = !matches!(
+                command,
+                BankAccountCommand::OpenAccount { .. }
+                    | BankAccountCommand::SetCategoryBudget { .. }
+                    | BankAccountCommand::PlaceHold { .. }
+                    | BankAccountCommand::ReleaseHold { .. }
+                    | BankAccountCommand::AuthorizeCharge { .. }
+                    | BankAccountCommand::SetInterestRate { .. }
+                    | BankAccountCommand::VerifyKyc { .. }
+                    | BankAccountCommand::SetNickname { .. }
+                    | BankAccountCommand::SetRoundUp { .. }
+                    | BankAccountCommand::RequestStatement { .. }
+                    | BankAccountCommand::SetTransactionLimit { .. }
+                    | BankAccountCommand::DissolveBucket { .. }
+                    | BankAccountCommand::SetRejectionAudit { .. }
+                    | BankAccountCommand::SetExpiration { .. }
+                    | BankAccountCommand::CloseAccount
+                    | BankAccountCommand::HoldDisputedFunds { .. }
+                    | BankAccountCommand::PauseInterest
+                    | BankAccountCommand::ResumeInterest
+                    | BankAccountCommand::TransferBranch { .. }
+                    | BankAccountCommand::ConfigureSweep { .. }
+                    | BankAccountCommand::RecordBalanceSnapshot
+                    | BankAccountCommand::EarnPoints { .. }
+                    | BankAccountCommand::RedeemPoints { .. }
+                    | BankAccountCommand::FlagForReview { .. }
+                    | BankAccountCommand::ClearReview
+                    | BankAccountCommand::PlaceGarnishment { .. }
+                    | BankAccountCommand::ReleaseGarnishment { .. }
+                    | BankAccountCommand::ScheduleRecurringDeposit { .. }
+                    | BankAccountCommand::CancelRecurringDeposit { .. }
+                    | BankAccountCommand::VoidOpen
+            );
+            if moves_money {
+                let result = account.handle(command, &services).await;
+                assert_eq!(
+                    result,
+                    Err(BankAccountError("kyc required".to_string())),
+                    "{}",
+                    command_type
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kyc_still_required_after_void_open() {
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::AccountOpened {
+            account_id: "acct-1".to_string(),
+            max_holds: None,
+            first_withdrawal_allowed_after: None,
+            branch_id: None,
+        });
+        account.apply(BankAccountEvent::AccountOpenVoided);
+        let result = account
+            .handle(
+                BankAccountCommand::DepositMoney {
+                    amount: positive(200.0),
+                },
+                &BankAccountServices::default(),
+            )
+            .await;
+        assert_eq!(result, Err(BankAccountError("kyc required".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_kyc_not_required_for_legacy_unopened_account() {
+        let mut account = BankAccount::default();
+        account.apply(BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        });
+        let services = BankAccountServices::default();
+
+        let deposit = account
+            .handle(
+                BankAccountCommand::DepositMoney {
+                    amount: positive(50.0),
+                },
+                &services,
+            )
+            .await;
+        assert!(deposit.is_ok());
+        let withdrawal = account
+            .handle(
+                BankAccountCommand::WithdrawMoney {
+                    amount: positive(50.0),
+                    category: None,
+                    mcc: None,
+                },
+                &services,
+            )
+            .await;
+        assert!(withdrawal.is_ok());
     }
 }
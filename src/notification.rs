@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use cqrs_es::{DomainEvent, EventEnvelope, Query};
+
+use crate::{BankAccount, BankAccountEvent};
+
+/// Delivers a customer notification (email, SMS, ...) for an account event.
+#[async_trait]
+pub trait Notifier: Sync + Send {
+    async fn notify(&self, event: &BankAccountEvent, account_id: &str);
+}
+
+/// A `Notifier` that logs each notification at info level.
+pub struct LoggingNotifier;
+
+#[async_trait]
+impl Notifier for LoggingNotifier {
+    async fn notify(&self, event: &BankAccountEvent, account_id: &str) {
+        log::info!("notify {}: {:?}", account_id, event);
+    }
+}
+
+/// Forwards committed events of the configured types to a `Notifier`.
+pub struct NotificationQuery<N> {
+    notifier: N,
+    event_types: HashSet<String>,
+}
+
+impl<N: Notifier> NotificationQuery<N> {
+    pub fn new(notifier: N, event_types: &[&str]) -> Self {
+        Self {
+            notifier,
+            event_types: event_types.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl<N: Notifier> Query<BankAccount> for NotificationQuery<N> {
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<BankAccount>]) {
+        for event in events {
+            if self.event_types.contains(&event.payload.event_type()) {
+                self.notifier.notify(&event.payload, aggregate_id).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct RecordingNotifier {
+        sent: Arc<Mutex<Vec<(String, BankAccountEvent)>>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, event: &BankAccountEvent, account_id: &str) {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((account_id.to_string(), event.clone()));
+        }
+    }
+
+    fn envelope(sequence: usize, payload: BankAccountEvent) -> EventEnvelope<BankAccount> {
+        EventEnvelope {
+            aggregate_id: "acct-1".to_string(),
+            sequence,
+            payload,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifies_only_configured_event_types() {
+        let notifier = RecordingNotifier::default();
+        let query = NotificationQuery::new(notifier.clone(), &["AccountClosed"]);
+        let closed = BankAccountEvent::AccountClosed {
+            payout_destination: None,
+        };
+
+        query
+            .dispatch(
+                "acct-1",
+                &[
+                    envelope(
+                        1,
+                        BankAccountEvent::CustomerWithdrewCash {
+                            amount: 200.0,
+                            balance: 0.0,
+                        },
+                    ),
+                    envelope(2, closed.clone()),
+                ],
+            )
+            .await;
+
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(*sent, vec![("acct-1".to_string(), closed)]);
+    }
+}
@@ -0,0 +1,1240 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+use cqrs_es::persist::GenericQuery;
+use cqrs_es::{EventEnvelope, Query, View};
+use serde::{Deserialize, Serialize};
+
+use crate::date::Date;
+use crate::view_repository::MemViewRepository;
+use crate::{BankAccount, BankAccountEvent};
+
+const RECENT_ACTIVITY_SIZE: usize = 20;
+
+/// Metadata key holding the RFC 3339 time at which an event was committed.
+pub const TIMESTAMP_METADATA_KEY: &str = "timestamp";
+
+/// The calendar date of an event's `timestamp` metadata, if it has one.
+pub fn event_date(event: &EventEnvelope<BankAccount>) -> Option<Date> {
+    event
+        .metadata
+        .get(TIMESTAMP_METADATA_KEY)
+        .and_then(|timestamp| timestamp.get(..10))
+        .and_then(|date| date.parse().ok())
+}
+
+/// The date an event takes effect: its effective date if it was backdated, otherwise
+/// the date it was committed.
+pub fn effective_date(event: &EventEnvelope<BankAccount>) -> Option<Date> {
+    match &event.payload {
+        BankAccountEvent::BackdatedTransactionPosted { effective_date, .. } => {
+            Some(*effective_date)
+        }
+        _ => event_date(event),
+    }
+}
+
+/// The balance at the end of `date`, summing only from the latest `BalanceSnapshotted`
+/// taken on or before that date. Transactions count toward their effective date, so a
+/// posting committed later but backdated to `date` or earlier is included. Events are
+/// expected in stream order; those without a timestamp are assumed to fall within the date.
+pub fn balance_as_of(events: &[EventEnvelope<BankAccount>], date: Date) -> f64 {
+    let snapshot = events.iter().rposition(|event| {
+        matches!(
+            &event.payload,
+            BankAccountEvent::BalanceSnapshotted { as_of, .. }
+                if Date::from_system_time(*as_of) <= date
+        )
+    });
+    let (start, mut balance) = match snapshot.map(|index| (index, &events[index].payload)) {
+        Some((index, BankAccountEvent::BalanceSnapshotted { balance, .. })) => {
+            (index + 1, *balance)
+        }
+        _ => (0, 0_f64),
+    };
+
+    for event in &events[start..] {
+        if effective_date(event).is_some_and(|effective| effective > date) {
+            continue;
+        }
+        match &event.payload {
+            BankAccountEvent::BalanceEstablished {
+                balance: established,
+            } => balance = *established,
+            BankAccountEvent::Redenominated { ratio, .. } => balance *= ratio,
+            payload => {
+                if let Some(entry) = LedgerEntry::from_event(payload) {
+                    balance += entry.amount;
+                }
+            }
+        }
+    }
+    balance
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LedgerEntry {
+    pub description: String,
+    pub amount: f64,
+}
+
+impl LedgerEntry {
+    fn new(description: &str, amount: f64) -> Self {
+        Self {
+            description: description.to_string(),
+            amount,
+        }
+    }
+
+    /// The entry an event would post to the ledger, if it moves money. `Redenominated` and
+    /// `BalanceEstablished` change the balance without posting an entry, so views that keep
+    /// balances rebase on them directly.
+    pub fn from_event(event: &BankAccountEvent) -> Option<Self> {
+        match event {
+            BankAccountEvent::CustomerDepositedMoney { amount, .. } => {
+                Some(LedgerEntry::new("deposit", *amount))
+            }
+            BankAccountEvent::CustomerWithdrewCash { amount, .. } => {
+                Some(LedgerEntry::new("withdrawal", -amount))
+            }
+            BankAccountEvent::CustomerWroteCheck {
+                check_number,
+                amount,
+                ..
+            } => Some(LedgerEntry::new(
+                &format!("check {}", check_number),
+                -amount,
+            )),
+            BankAccountEvent::BillPaid { payee, amount, .. } => Some(LedgerEntry::new(
+                &format!("bill payment {}", payee),
+                -amount,
+            )),
+            BankAccountEvent::BonusApplied {
+                bonus_id, amount, ..
+            } => Some(LedgerEntry::new(&format!("bonus {}", bonus_id), *amount)),
+            BankAccountEvent::WireReceived { sender, amount, .. } => {
+                Some(LedgerEntry::new(&format!("wire from {}", sender), *amount))
+            }
+            BankAccountEvent::CustomerTransferredMoney {
+                to_account_id,
+                amount,
+                ..
+            } => Some(LedgerEntry::new(
+                &format!("transfer to {}", to_account_id),
+                -amount,
+            )),
+            BankAccountEvent::ChargeCaptured {
+                auth_id, amount, ..
+            } => Some(LedgerEntry::new(&format!("charge {}", auth_id), -amount)),
+            BankAccountEvent::BackdatedTransactionPosted { amount, reason, .. } => {
+                Some(LedgerEntry::new(&format!("backdated: {}", reason), *amount))
+            }
+            BankAccountEvent::DisputeResolved {
+                dispute_id,
+                in_customer_favor: false,
+                amount,
+                ..
+            } => Some(LedgerEntry::new(
+                &format!("dispute {}", dispute_id),
+                -amount,
+            )),
+            BankAccountEvent::InterestAccrued { amount, .. } => {
+                Some(LedgerEntry::new("interest", *amount))
+            }
+            BankAccountEvent::InterestCorrected {
+                original_amount,
+                corrected_amount,
+                ..
+            } => Some(LedgerEntry::new(
+                "interest correction",
+                corrected_amount - original_amount,
+            )),
+            BankAccountEvent::BalanceAbsorbed {
+                from_account_id,
+                amount,
+                ..
+            } => Some(LedgerEntry::new(
+                &format!("absorbed from {}", from_account_id),
+                *amount,
+            )),
+            BankAccountEvent::AccountSpunOff {
+                new_account_id,
+                amount,
+                ..
+            } => Some(LedgerEntry::new(
+                &format!("spin-off to {}", new_account_id),
+                -amount,
+            )),
+            BankAccountEvent::TellerDeposited {
+                teller_id, amount, ..
+            } => Some(LedgerEntry::new(
+                &format!("teller deposit {}", teller_id),
+                *amount,
+            )),
+            BankAccountEvent::TellerWithdrew {
+                teller_id, amount, ..
+            } => Some(LedgerEntry::new(
+                &format!("teller withdrawal {}", teller_id),
+                -amount,
+            )),
+            BankAccountEvent::TaxWithheld {
+                amount, tax_year, ..
+            } => Some(LedgerEntry::new(
+                &format!("tax withheld {}", tax_year),
+                -amount,
+            )),
+            BankAccountEvent::TransactionReversed {
+                original_sequence,
+                amount,
+                ..
+            } => Some(LedgerEntry::new(
+                &format!("reversal of {}", original_sequence),
+                *amount,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// The most recent transactions on an account, oldest first.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RecentActivityView {
+    entries: VecDeque<LedgerEntry>,
+}
+
+impl RecentActivityView {
+    pub fn recent(&self) -> &[LedgerEntry] {
+        // `update` keeps the buffer contiguous, so the first slice holds every entry.
+        self.entries.as_slices().0
+    }
+}
+
+impl View<BankAccount> for RecentActivityView {
+    fn update(&mut self, event: &EventEnvelope<BankAccount>) {
+        match &event.payload {
+            // Earlier entries convert to the new currency, as the account's own history does.
+            BankAccountEvent::Redenominated { ratio, .. } => {
+                for entry in &mut self.entries {
+                    entry.amount *= ratio;
+                }
+            }
+            // A checkpoint replaces the history before it.
+            BankAccountEvent::BalanceEstablished { .. } => self.entries.clear(),
+            _ => {}
+        }
+        if let Some(entry) = LedgerEntry::from_event(&event.payload) {
+            if self.entries.len() == RECENT_ACTIVITY_SIZE {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(entry);
+            self.entries.make_contiguous();
+        }
+    }
+}
+
+/// Transaction count and total money moved per day.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DailyVolumeView {
+    days: BTreeMap<Date, (u32, f64)>,
+}
+
+impl DailyVolumeView {
+    pub fn volume_on(&self, date: Date) -> (u32, f64) {
+        self.days.get(&date).copied().unwrap_or_default()
+    }
+}
+
+impl View<BankAccount> for DailyVolumeView {
+    fn update(&mut self, event: &EventEnvelope<BankAccount>) {
+        let (date, entry) = match (
+            effective_date(event),
+            LedgerEntry::from_event(&event.payload),
+        ) {
+            (Some(date), Some(entry)) => (date, entry),
+            _ => return,
+        };
+        let (count, volume) = self.days.entry(date).or_default();
+        *count += 1;
+        *volume += entry.amount.abs();
+    }
+}
+
+/// A completed month's transactions, with the balances either side of them.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Statement {
+    pub opening_balance: f64,
+    pub closing_balance: f64,
+    pub entries: Vec<LedgerEntry>,
+}
+
+/// Monthly statements keyed by `YYYY-MM`; a month is only finalized once an event from a
+/// later month arrives.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MonthlyStatementView {
+    statements: BTreeMap<String, Statement>,
+    current: Option<(String, Statement)>,
+    /// The opening balance of the first month, set by a `BalanceEstablished` checkpoint.
+    #[serde(default)]
+    established_balance: f64,
+}
+
+impl MonthlyStatementView {
+    /// The finalized statement for a month, or `None` while the month is still open.
+    pub fn statement_for(&self, year: i32, month: u32) -> Option<&Statement> {
+        self.statements.get(&format!("{:04}-{:02}", year, month))
+    }
+
+    fn open_month(&mut self, month: String) {
+        let opening_balance = match self.current.take() {
+            Some((previous, statement)) => {
+                let closing_balance = statement.closing_balance;
+                self.statements.insert(previous, statement);
+                closing_balance
+            }
+            None => self.established_balance,
+        };
+        let statement = Statement {
+            opening_balance,
+            closing_balance: opening_balance,
+            entries: Vec::new(),
+        };
+        self.current = Some((month, statement));
+    }
+
+    /// Adds a backdated `entry` to the finalized statement for `month`, carrying its amount
+    /// through the balances of every later statement.
+    fn restate(&mut self, month: String, entry: LedgerEntry) {
+        let amount = entry.amount;
+        let opening_balance = self
+            .statements
+            .range(..month.clone())
+            .next_back()
+            .map_or(0_f64, |(_, previous)| previous.closing_balance);
+        let statement = self.statements.entry(month.clone()).or_insert(Statement {
+            opening_balance,
+            closing_balance: opening_balance,
+            entries: Vec::new(),
+        });
+        statement.closing_balance += amount;
+        statement.entries.push(entry);
+
+        let later = self
+            .statements
+            .range_mut(month..)
+            .skip(1)
+            .map(|(_, later)| later);
+        for later in later.chain(self.current.as_mut().map(|(_, current)| current)) {
+            later.opening_balance += amount;
+            later.closing_balance += amount;
+        }
+    }
+
+    /// Applies a balance change that posts no entry to the month it falls in. A finalized
+    /// month keeps the balances it was issued with.
+    fn rebase(&mut self, event: &EventEnvelope<BankAccount>) {
+        if let Some(date) = effective_date(event) {
+            let month = format!("{:04}-{:02}", date.year(), date.month());
+            if self
+                .current
+                .as_ref()
+                .is_none_or(|(current, _)| *current < month)
+            {
+                self.open_month(month);
+            }
+        }
+        let statement = match &mut self.current {
+            Some((_, statement)) => statement,
+            None => {
+                if let BankAccountEvent::BalanceEstablished { balance } = &event.payload {
+                    self.established_balance = *balance;
+                }
+                return;
+            }
+        };
+        match &event.payload {
+            // The whole open month converts, so its entries still sum to its balances.
+            BankAccountEvent::Redenominated { ratio, .. } => {
+                statement.opening_balance *= ratio;
+                statement.closing_balance *= ratio;
+                for entry in &mut statement.entries {
+                    entry.amount *= ratio;
+                }
+            }
+            BankAccountEvent::BalanceEstablished { balance } => {
+                statement.opening_balance += balance - statement.closing_balance;
+                statement.closing_balance = *balance;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl View<BankAccount> for MonthlyStatementView {
+    fn update(&mut self, event: &EventEnvelope<BankAccount>) {
+        if matches!(
+            event.payload,
+            BankAccountEvent::Redenominated { .. } | BankAccountEvent::BalanceEstablished { .. }
+        ) {
+            return self.rebase(event);
+        }
+        let (date, entry) = match (
+            effective_date(event),
+            LedgerEntry::from_event(&event.payload),
+        ) {
+            (Some(date), Some(entry)) => (date, entry),
+            _ => return,
+        };
+        let month = format!("{:04}-{:02}", date.year(), date.month());
+        match &self.current {
+            // Postings backdated into an already finalized month restate that month.
+            Some((current, _)) if *current > month => return self.restate(month, entry),
+            Some((current, _)) if *current == month => {}
+            _ => self.open_month(month),
+        }
+        let (_, statement) = self.current.as_mut().expect("a month was just opened");
+        statement.closing_balance += entry.amount;
+        statement.entries.push(entry);
+    }
+}
+
+/// Funds currently held on an account, in the order the holds were placed.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct HoldsView {
+    holds: Vec<(String, f64)>,
+}
+
+impl HoldsView {
+    pub fn active_holds(&self) -> &[(String, f64)] {
+        &self.holds
+    }
+
+    pub fn total_held(&self) -> f64 {
+        self.holds.iter().map(|(_, amount)| amount).sum()
+    }
+
+    fn remove(&mut self, hold_id: &str) {
+        self.holds.retain(|(id, _)| id != hold_id);
+    }
+}
+
+impl View<BankAccount> for HoldsView {
+    fn update(&mut self, event: &EventEnvelope<BankAccount>) {
+        match &event.payload {
+            BankAccountEvent::HoldPlaced {
+                hold_id, amount, ..
+            } => self.holds.push((hold_id.clone(), *amount)),
+            BankAccountEvent::DisputedFundsHeld {
+                dispute_id, amount, ..
+            } => self.holds.push((dispute_id.clone(), *amount)),
+            BankAccountEvent::HoldReleased { hold_id, .. } => self.remove(hold_id),
+            BankAccountEvent::DisputeResolved { dispute_id, .. } => self.remove(dispute_id),
+            BankAccountEvent::ChargeCaptured { auth_id, .. } => self.remove(auth_id),
+            // Held funds convert to the new currency along with the account's own holds.
+            BankAccountEvent::Redenominated { ratio, .. } => {
+                for (_, amount) in &mut self.holds {
+                    *amount *= ratio;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Spending per merchant category code, from withdrawals and checks that carried one.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SpendByCategoryView {
+    totals: BTreeMap<String, f64>,
+}
+
+impl SpendByCategoryView {
+    pub fn totals(&self) -> &BTreeMap<String, f64> {
+        &self.totals
+    }
+
+    pub fn total_for(&self, mcc: &str) -> f64 {
+        self.totals.get(mcc).copied().unwrap_or(0.0)
+    }
+}
+
+impl View<BankAccount> for SpendByCategoryView {
+    fn update(&mut self, event: &EventEnvelope<BankAccount>) {
+        if let BankAccountEvent::MerchantSpendRecorded { mcc, amount } = &event.payload {
+            *self.totals.entry(mcc.clone()).or_default() += amount;
+        }
+    }
+}
+
+/// Interest earned per calendar year of the event timestamp, for tax reporting. Negative
+/// accruals and corrections net against the year's total.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct InterestEarnedView {
+    years: BTreeMap<i32, f64>,
+}
+
+impl InterestEarnedView {
+    pub fn interest_for_year(&self, year: i32) -> f64 {
+        self.years.get(&year).copied().unwrap_or(0.0)
+    }
+}
+
+impl View<BankAccount> for InterestEarnedView {
+    fn update(&mut self, event: &EventEnvelope<BankAccount>) {
+        let amount = match &event.payload {
+            BankAccountEvent::InterestAccrued { amount, .. } => *amount,
+            BankAccountEvent::InterestCorrected {
+                original_amount,
+                corrected_amount,
+                ..
+            } => corrected_amount - original_amount,
+            _ => return,
+        };
+        if let Some(date) = event_date(event) {
+            *self.years.entry(date.year()).or_default() += amount;
+        }
+    }
+}
+
+/// The views maintained for every account, each in its own in-memory repository.
+#[derive(Default)]
+pub struct DefaultViews {
+    pub recent_activity: Arc<MemViewRepository<RecentActivityView>>,
+    pub daily_volume: Arc<MemViewRepository<DailyVolumeView>>,
+    pub holds: Arc<MemViewRepository<HoldsView>>,
+    pub monthly_statements: Arc<MemViewRepository<MonthlyStatementView>>,
+    pub spend_by_category: Arc<MemViewRepository<SpendByCategoryView>>,
+    pub interest_earned: Arc<MemViewRepository<InterestEarnedView>>,
+}
+
+/// Queries keeping every `DefaultViews` repository up to date, ready for `CqrsFramework::new`.
+pub fn default_queries(views: &DefaultViews) -> Vec<Box<dyn Query<BankAccount>>> {
+    vec![
+        Box::new(GenericQuery::new(Arc::clone(&views.recent_activity))),
+        Box::new(GenericQuery::new(Arc::clone(&views.daily_volume))),
+        Box::new(GenericQuery::new(Arc::clone(&views.holds))),
+        Box::new(GenericQuery::new(Arc::clone(&views.monthly_statements))),
+        Box::new(GenericQuery::new(Arc::clone(&views.spend_by_category))),
+        Box::new(GenericQuery::new(Arc::clone(&views.interest_earned))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use cqrs_es::mem_store::MemStore;
+    use cqrs_es::persist::ViewRepository;
+    use cqrs_es::CqrsFramework;
+
+    use crate::testing::TimedReplay;
+    use crate::{BankAccountCommand, BankAccountServices, PositiveAmount};
+
+    fn envelope(sequence: usize, payload: BankAccountEvent) -> EventEnvelope<BankAccount> {
+        EventEnvelope {
+            aggregate_id: "acct-1".to_string(),
+            sequence,
+            payload,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn timestamped(
+        sequence: usize,
+        timestamp: &str,
+        payload: BankAccountEvent,
+    ) -> EventEnvelope<BankAccount> {
+        let mut envelope = envelope(sequence, payload);
+        envelope
+            .metadata
+            .insert(TIMESTAMP_METADATA_KEY.to_string(), timestamp.to_string());
+        envelope
+    }
+
+    #[test]
+    fn test_balance_as_of_starts_from_snapshot() {
+        let events = vec![
+            timestamped(
+                1,
+                "2023-05-01T09:00:00Z",
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+            ),
+            timestamped(
+                2,
+                "2023-05-31T23:00:00Z",
+                BankAccountEvent::BalanceSnapshotted {
+                    balance: 500.0,
+                    as_of: Date::from_ymd(2023, 5, 31).unwrap().start_of_day(),
+                },
+            ),
+            timestamped(
+                3,
+                "2023-06-02T09:00:00Z",
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 120.0,
+                    balance: 380.0,
+                },
+            ),
+            timestamped(
+                4,
+                "2023-06-05T09:00:00Z",
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 80.0,
+                    balance: 300.0,
+                },
+            ),
+        ];
+
+        assert_eq!(
+            balance_as_of(&events, Date::from_ymd(2023, 5, 15).unwrap()),
+            500.0
+        );
+        assert_eq!(
+            balance_as_of(&events, Date::from_ymd(2023, 6, 3).unwrap()),
+            380.0
+        );
+        assert_eq!(
+            balance_as_of(&events, Date::from_ymd(2023, 6, 30).unwrap()),
+            300.0
+        );
+    }
+
+    #[test]
+    fn test_balance_as_of_counts_backdated_postings() {
+        let events = vec![
+            timestamped(
+                1,
+                "2023-05-01T09:00:00Z",
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+            ),
+            timestamped(
+                2,
+                "2023-05-20T09:00:00Z",
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 120.0,
+                    balance: 380.0,
+                },
+            ),
+            timestamped(
+                3,
+                "2023-05-25T09:00:00Z",
+                BankAccountEvent::BackdatedTransactionPosted {
+                    amount: 35.0,
+                    effective_date: Date::from_ymd(2023, 5, 12).unwrap(),
+                    reason: "missed interest credit".to_string(),
+                    balance: 415.0,
+                },
+            ),
+        ];
+
+        assert_eq!(
+            balance_as_of(&events, Date::from_ymd(2023, 5, 15).unwrap()),
+            535.0
+        );
+        assert_eq!(
+            balance_as_of(&events, Date::from_ymd(2023, 5, 31).unwrap()),
+            415.0
+        );
+    }
+
+    #[test]
+    fn test_recent_activity_keeps_last_twenty() {
+        let mut view = RecentActivityView::default();
+        for n in 1..=25 {
+            let amount = n as f64;
+            view.update(&envelope(
+                n,
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount,
+                    balance: amount,
+                },
+            ));
+        }
+
+        let recent = view.recent();
+        assert_eq!(recent.len(), 20);
+        let amounts: Vec<f64> = recent.iter().map(|entry| entry.amount).collect();
+        let expected: Vec<f64> = (6..=25).map(|n| n as f64).collect();
+        assert_eq!(amounts, expected);
+    }
+
+    #[test]
+    fn test_recent_activity_skips_non_transactions() {
+        let mut view = RecentActivityView::default();
+        view.update(&envelope(
+            1,
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: None,
+            },
+        ));
+        view.update(&envelope(
+            2,
+            BankAccountEvent::CustomerWroteCheck {
+                check_number: "1170".to_string(),
+                amount: 50.0,
+                balance: -50.0,
+            },
+        ));
+
+        assert_eq!(view.recent(), &[LedgerEntry::new("check 1170", -50.0)]);
+    }
+
+    #[test]
+    fn test_daily_volume_across_two_days() {
+        let mut view = DailyVolumeView::default();
+        view.update(&timestamped(
+            1,
+            "2023-05-31T09:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 200.0,
+                balance: 200.0,
+            },
+        ));
+        view.update(&timestamped(
+            2,
+            "2023-05-31T17:30:00Z",
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            },
+        ));
+        view.update(&timestamped(
+            3,
+            "2023-06-01T08:15:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 25.0,
+                balance: 175.0,
+            },
+        ));
+        view.update(&timestamped(
+            4,
+            "2023-06-01T08:16:00Z",
+            BankAccountEvent::CategoryBudgetSet {
+                category: "groceries".to_string(),
+                limit: 100.0,
+            },
+        ));
+
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 5, 31).unwrap()),
+            (2, 250.0)
+        );
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 6, 1).unwrap()),
+            (1, 25.0)
+        );
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 6, 2).unwrap()),
+            (0, 0.0)
+        );
+
+        let json = serde_json::to_string(&view).unwrap();
+        let restored: DailyVolumeView = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.volume_on(Date::from_ymd(2023, 5, 31).unwrap()),
+            (2, 250.0)
+        );
+    }
+
+    #[test]
+    fn test_daily_volume_from_timed_replay() {
+        let may_31 = Date::from_ymd(2023, 5, 31).unwrap().start_of_day();
+        let june_1 = Date::from_ymd(2023, 6, 1).unwrap().start_of_day();
+        let view: DailyVolumeView = TimedReplay::new(vec![
+            (
+                may_31 + Duration::from_secs(23 * 3_600 + 59 * 60),
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 200.0,
+                    balance: 200.0,
+                },
+            ),
+            (
+                june_1 + Duration::from_secs(60),
+                BankAccountEvent::CustomerWithdrewCash {
+                    amount: 50.0,
+                    balance: 150.0,
+                },
+            ),
+            (
+                june_1 + Duration::from_secs(3_600),
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 25.0,
+                    balance: 175.0,
+                },
+            ),
+        ])
+        .replay();
+
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 5, 31).unwrap()),
+            (1, 200.0)
+        );
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 6, 1).unwrap()),
+            (2, 75.0)
+        );
+    }
+
+    #[test]
+    fn test_daily_volume_uses_effective_date() {
+        let mut view = DailyVolumeView::default();
+        view.update(&timestamped(
+            1,
+            "2023-05-31T09:00:00Z",
+            BankAccountEvent::BackdatedTransactionPosted {
+                amount: 35.0,
+                effective_date: Date::from_ymd(2023, 5, 12).unwrap(),
+                reason: "missed interest credit".to_string(),
+                balance: 35.0,
+            },
+        ));
+
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 5, 12).unwrap()),
+            (1, 35.0)
+        );
+        assert_eq!(
+            view.volume_on(Date::from_ymd(2023, 5, 31).unwrap()),
+            (0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_monthly_statement_finalized_by_next_month() {
+        let mut view = MonthlyStatementView::default();
+        view.update(&timestamped(
+            1,
+            "2023-05-02T09:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+        ));
+        view.update(&timestamped(
+            2,
+            "2023-05-31T18:00:00Z",
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 120.0,
+                balance: 380.0,
+            },
+        ));
+        assert_eq!(view.statement_for(2023, 5), None);
+
+        view.update(&timestamped(
+            3,
+            "2023-06-01T08:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 20.0,
+                balance: 400.0,
+            },
+        ));
+
+        assert_eq!(
+            view.statement_for(2023, 5),
+            Some(&Statement {
+                opening_balance: 0.0,
+                closing_balance: 380.0,
+                entries: vec![
+                    LedgerEntry::new("deposit", 500.0),
+                    LedgerEntry::new("withdrawal", -120.0),
+                ],
+            })
+        );
+        assert_eq!(view.statement_for(2023, 6), None);
+    }
+
+    #[test]
+    fn test_backdated_posting_restates_finalized_month() {
+        let mut view = MonthlyStatementView::default();
+        view.update(&timestamped(
+            1,
+            "2023-05-02T09:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+        ));
+        view.update(&timestamped(
+            2,
+            "2023-06-01T08:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 20.0,
+                balance: 520.0,
+            },
+        ));
+        view.update(&timestamped(
+            3,
+            "2023-07-03T08:00:00Z",
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 100.0,
+                balance: 420.0,
+            },
+        ));
+        view.update(&timestamped(
+            4,
+            "2023-07-05T09:00:00Z",
+            BankAccountEvent::BackdatedTransactionPosted {
+                amount: 35.0,
+                effective_date: Date::from_ymd(2023, 5, 12).unwrap(),
+                reason: "missed interest credit".to_string(),
+                balance: 455.0,
+            },
+        ));
+        view.update(&timestamped(
+            5,
+            "2023-08-01T08:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 45.0,
+                balance: 500.0,
+            },
+        ));
+
+        assert_eq!(
+            view.statement_for(2023, 5),
+            Some(&Statement {
+                opening_balance: 0.0,
+                closing_balance: 535.0,
+                entries: vec![
+                    LedgerEntry::new("deposit", 500.0),
+                    LedgerEntry::new("backdated: missed interest credit", 35.0),
+                ],
+            })
+        );
+        assert_eq!(
+            view.statement_for(2023, 6),
+            Some(&Statement {
+                opening_balance: 535.0,
+                closing_balance: 555.0,
+                entries: vec![LedgerEntry::new("deposit", 20.0)],
+            })
+        );
+        assert_eq!(
+            view.statement_for(2023, 7),
+            Some(&Statement {
+                opening_balance: 555.0,
+                closing_balance: 455.0,
+                entries: vec![LedgerEntry::new("withdrawal", -100.0)],
+            })
+        );
+    }
+
+    #[test]
+    fn test_monthly_statement_rebased_by_redenomination() {
+        let mut view = MonthlyStatementView::default();
+        view.update(&envelope(
+            1,
+            BankAccountEvent::BalanceEstablished { balance: 300.0 },
+        ));
+        view.update(&timestamped(
+            2,
+            "2023-05-02T09:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 100.0,
+                balance: 400.0,
+            },
+        ));
+        view.update(&timestamped(
+            3,
+            "2023-06-01T08:00:00Z",
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 350.0,
+            },
+        ));
+        view.update(&timestamped(
+            4,
+            "2023-06-15T08:00:00Z",
+            BankAccountEvent::Redenominated {
+                old_currency: None,
+                new_currency: "EUR".to_string(),
+                ratio: 2.0,
+                balance: 700.0,
+            },
+        ));
+        view.update(&timestamped(
+            5,
+            "2023-07-03T08:00:00Z",
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 20.0,
+                balance: 720.0,
+            },
+        ));
+
+        assert_eq!(
+            view.statement_for(2023, 5),
+            Some(&Statement {
+                opening_balance: 300.0,
+                closing_balance: 400.0,
+                entries: vec![LedgerEntry::new("deposit", 100.0)],
+            })
+        );
+        assert_eq!(
+            view.statement_for(2023, 6),
+            Some(&Statement {
+                opening_balance: 800.0,
+                closing_balance: 700.0,
+                entries: vec![LedgerEntry::new("withdrawal", -100.0)],
+            })
+        );
+    }
+
+    #[test]
+    fn test_recent_activity_rebased_by_redenomination() {
+        let mut view = RecentActivityView::default();
+        view.update(&envelope(
+            1,
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 100.0,
+                balance: 100.0,
+            },
+        ));
+        view.update(&envelope(
+            2,
+            BankAccountEvent::Redenominated {
+                old_currency: None,
+                new_currency: "EUR".to_string(),
+                ratio: 2.0,
+                balance: 200.0,
+            },
+        ));
+        view.update(&envelope(
+            3,
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: 50.0,
+                balance: 150.0,
+            },
+        ));
+
+        assert_eq!(
+            view.recent(),
+            &[
+                LedgerEntry::new("deposit", 200.0),
+                LedgerEntry::new("withdrawal", -50.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_holds_view_breakdown() {
+        let placed_at = Date::from_ymd(2023, 5, 31).unwrap().start_of_day();
+        let mut view = HoldsView::default();
+        view.update(&envelope(
+            1,
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-1".to_string(),
+                amount: 40.0,
+                placed_at,
+            },
+        ));
+        view.update(&envelope(
+            2,
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: 25.0,
+                placed_at,
+            },
+        ));
+        view.update(&envelope(
+            3,
+            BankAccountEvent::HoldReleased {
+                hold_id: "hold-1".to_string(),
+                released_at: placed_at,
+            },
+        ));
+
+        assert_eq!(view.active_holds(), &[("auth-1".to_string(), 25.0)]);
+        assert_eq!(view.total_held(), 25.0);
+
+        view.update(&envelope(
+            4,
+            BankAccountEvent::ChargeCaptured {
+                auth_id: "auth-1".to_string(),
+                amount: 20.0,
+                balance: 80.0,
+                captured_at: placed_at,
+            },
+        ));
+        assert!(view.active_holds().is_empty());
+    }
+
+    #[test]
+    fn test_holds_view_redenominated() {
+        let placed_at = Date::from_ymd(2023, 5, 31).unwrap().start_of_day();
+        let mut view = HoldsView::default();
+        view.update(&envelope(
+            1,
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-1".to_string(),
+                amount: 40_000.0,
+                placed_at,
+            },
+        ));
+        view.update(&envelope(
+            2,
+            BankAccountEvent::Redenominated {
+                old_currency: None,
+                new_currency: "NEW".to_string(),
+                ratio: 0.001,
+                balance: 100.0,
+            },
+        ));
+
+        assert_eq!(view.active_holds(), &[("hold-1".to_string(), 40.0)]);
+        assert_eq!(view.total_held(), 40.0);
+    }
+
+    #[test]
+    fn test_spend_by_category_view_totals() {
+        let mut view = SpendByCategoryView::default();
+        for (sequence, mcc, amount) in [(1, "5411", 40.0), (2, "5812", 15.0), (3, "5411", 10.0)] {
+            view.update(&envelope(
+                sequence,
+                BankAccountEvent::MerchantSpendRecorded {
+                    mcc: mcc.to_string(),
+                    amount,
+                },
+            ));
+        }
+
+        assert_eq!(view.total_for("5411"), 50.0);
+        assert_eq!(view.total_for("5812"), 15.0);
+        assert_eq!(view.total_for("4111"), 0.0);
+    }
+
+    #[test]
+    fn test_interest_earned_by_year() {
+        let mut view = InterestEarnedView::default();
+        for (sequence, timestamp, amount, balance) in [
+            (1, "2022-11-30T00:00:00Z", 4.0, 1_004.0),
+            (2, "2022-12-31T00:00:00Z", 4.5, 1_008.5),
+            (3, "2023-01-31T00:00:00Z", 5.0, 1_013.5),
+            (4, "2023-02-28T00:00:00Z", -1.5, 1_012.0),
+        ] {
+            view.update(&timestamped(
+                sequence,
+                timestamp,
+                BankAccountEvent::InterestAccrued { amount, balance },
+            ));
+        }
+
+        assert_eq!(view.interest_for_year(2022), 8.5);
+        assert_eq!(view.interest_for_year(2023), 3.5);
+        assert_eq!(view.interest_for_year(2021), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_default_queries_update_every_view() {
+        let views = DefaultViews::default();
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            default_queries(&views),
+            BankAccountServices::default(),
+        );
+        let commands = [
+            (
+                "2023-05-31T09:00:00Z",
+                BankAccountCommand::SetInterestRate { annual_rate: 0.5 },
+            ),
+            (
+                "2023-05-31T09:00:00Z",
+                BankAccountCommand::DepositMoney {
+                    amount: PositiveAmount::new(200.0).unwrap(),
+                },
+            ),
+            (
+                "2023-05-31T10:00:00Z",
+                BankAccountCommand::WithdrawMoney {
+                    amount: PositiveAmount::new(40.0).unwrap(),
+                    category: None,
+                    mcc: Some("5411".to_string()),
+                },
+            ),
+            (
+                "2023-05-31T11:00:00Z",
+                BankAccountCommand::PlaceHold {
+                    hold_id: "hold-1".to_string(),
+                    amount: 30.0,
+                },
+            ),
+            // 160 at 50% for 73 days earns exactly 16.
+            (
+                "2023-05-31T12:00:00Z",
+                BankAccountCommand::AccrueInterest { days: 73 },
+            ),
+            // The first June event finalizes May's statement.
+            (
+                "2023-06-01T09:00:00Z",
+                BankAccountCommand::DepositMoney {
+                    amount: PositiveAmount::new(10.0).unwrap(),
+                },
+            ),
+        ];
+        for (timestamp, command) in commands {
+            let metadata =
+                HashMap::from([(TIMESTAMP_METADATA_KEY.to_string(), timestamp.to_string())]);
+            cqrs.execute_with_metadata("acct-1", command, metadata)
+                .await
+                .unwrap();
+        }
+
+        let may = vec![
+            LedgerEntry::new("deposit", 200.0),
+            LedgerEntry::new("withdrawal", -40.0),
+            LedgerEntry::new("interest", 16.0),
+        ];
+        let recent_activity = views.recent_activity.load("acct-1").await.unwrap().unwrap();
+        let mut expected = may.clone();
+        expected.push(LedgerEntry::new("deposit", 10.0));
+        assert_eq!(recent_activity.recent(), expected.as_slice());
+
+        let daily_volume = views.daily_volume.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(
+            daily_volume.volume_on(Date::from_ymd(2023, 5, 31).unwrap()),
+            (3, 256.0)
+        );
+        assert_eq!(
+            daily_volume.volume_on(Date::from_ymd(2023, 6, 1).unwrap()),
+            (1, 10.0)
+        );
+
+        let holds = views.holds.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(holds.active_holds(), &[("hold-1".to_string(), 30.0)]);
+
+        let statements = views
+            .monthly_statements
+            .load("acct-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            statements.statement_for(2023, 5),
+            Some(&Statement {
+                opening_balance: 0.0,
+                closing_balance: 176.0,
+                entries: may,
+            })
+        );
+        assert_eq!(statements.statement_for(2023, 6), None);
+
+        let spend = views
+            .spend_by_category
+            .load("acct-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(spend.total_for("5411"), 40.0);
+        assert_eq!(spend.total_for("5812"), 0.0);
+
+        let interest = views.interest_earned.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(interest.interest_for_year(2023), 16.0);
+    }
+}
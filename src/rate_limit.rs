@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use cqrs_es::{AggregateError, CqrsFramework, EventStore};
+
+use crate::clock::Clock;
+use crate::{BankAccount, BankAccountCommand, BankAccountError};
+
+/// A token bucket per account: each command spends a token, and tokens refill continuously
+/// up to `capacity`. A bucket idle long enough to refill completely is forgotten, keeping the
+/// map bounded by the number of recently active accounts.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_second: f64,
+    clock: Box<dyn Clock>,
+    buckets: Mutex<HashMap<String, (f64, SystemTime)>>,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, refill_per_second: f64, clock: Box<dyn Clock>) -> Self {
+        Self {
+            capacity: f64::from(capacity),
+            refill_per_second,
+            clock,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// The tokens a bucket holding `tokens` at `last_refill` has at `now`.
+    fn refilled(&self, tokens: f64, last_refill: SystemTime, now: SystemTime) -> f64 {
+        let elapsed = now
+            .duration_since(last_refill)
+            .unwrap_or_default()
+            .as_secs_f64();
+        (tokens + elapsed * self.refill_per_second).min(self.capacity)
+    }
+
+    /// Spends a token for `account_id` as of `now`, or rejects the command when none remain.
+    /// Full buckets are pruned first.
+    pub fn acquire_at(&self, account_id: &str, now: SystemTime) -> Result<(), BankAccountError> {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.retain(|_, (tokens, last_refill)| {
+            self.refilled(*tokens, *last_refill, now) < self.capacity
+        });
+        let (tokens, last_refill) = buckets
+            .entry(account_id.to_string())
+            .or_insert((self.capacity, now));
+        *tokens = self.refilled(*tokens, *last_refill, now);
+        *last_refill = now;
+        if *tokens < 1_f64 {
+            return Err("rate limited".into());
+        }
+        *tokens -= 1_f64;
+        Ok(())
+    }
+
+    /// The number of accounts currently tracked.
+    pub fn len(&self) -> usize {
+        self.buckets.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Executes `command` through `cqrs` if the account is within its rate.
+    pub async fn execute<ES>(
+        &self,
+        cqrs: &CqrsFramework<BankAccount, ES>,
+        account_id: &str,
+        command: BankAccountCommand,
+    ) -> Result<(), AggregateError<BankAccountError>>
+    where
+        ES: EventStore<BankAccount>,
+    {
+        self.acquire_at(account_id, self.clock.now())
+            .map_err(AggregateError::UserError)?;
+        cqrs.execute(account_id, command).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use cqrs_es::mem_store::MemStore;
+
+    use crate::clock::{FixedClock, SystemClock};
+    use crate::{BankAccountServices, PositiveAmount};
+
+    #[tokio::test]
+    async fn test_rejects_commands_over_the_rate() {
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            vec![],
+            BankAccountServices::default(),
+        );
+        let limiter = RateLimiter::new(
+            3,
+            1.0 / 3_600.0,
+            Box::new(FixedClock(SystemTime::UNIX_EPOCH)),
+        );
+        let deposit = || BankAccountCommand::DepositMoney {
+            amount: PositiveAmount::new(10.0).unwrap(),
+        };
+
+        let mut results = Vec::new();
+        for _ in 0..5 {
+            results.push(limiter.execute(&cqrs, "acct-1", deposit()).await.is_ok());
+        }
+        assert_eq!(results, vec![true, true, true, false, false]);
+
+        // Other accounts have their own bucket.
+        assert!(limiter.execute(&cqrs, "acct-2", deposit()).await.is_ok());
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limiter = RateLimiter::new(2, 0.5, Box::new(SystemClock));
+        let start = SystemTime::UNIX_EPOCH;
+
+        assert!(limiter.acquire_at("acct-1", start).is_ok());
+        assert!(limiter.acquire_at("acct-1", start).is_ok());
+        assert_eq!(
+            limiter.acquire_at("acct-1", start),
+            Err(BankAccountError("rate limited".to_string()))
+        );
+        assert!(limiter
+            .acquire_at("acct-1", start + Duration::from_secs(2))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_idle_buckets_are_evicted() {
+        let limiter = RateLimiter::new(2, 0.5, Box::new(SystemClock));
+        let start = SystemTime::UNIX_EPOCH;
+
+        assert!(limiter.acquire_at("acct-1", start).is_ok());
+        assert!(limiter
+            .acquire_at("acct-2", start + Duration::from_secs(1))
+            .is_ok());
+        assert_eq!(limiter.len(), 2);
+
+        // acct-1 has refilled completely by now; acct-2 is still short of a token.
+        assert!(limiter
+            .acquire_at("acct-3", start + Duration::from_secs(2))
+            .is_ok());
+        assert_eq!(limiter.len(), 2);
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use cqrs_es::{Aggregate, AggregateContext, AggregateError, EventStore, Query};
+use serde::{Deserialize, Serialize};
+
+use crate::queries::{LedgerEntry, TIMESTAMP_METADATA_KEY};
+use crate::{
+    BankAccount, BankAccountCommand, BankAccountError, BankAccountEvent, BankAccountServices,
+};
+
+/// A customer-facing record of a completed transaction.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Receipt {
+    pub transaction_id: String,
+    pub timestamp: Option<String>,
+    pub amount: f64,
+    pub balance_after: f64,
+    pub description: String,
+}
+
+/// Executes `command` against the account in `store`, dispatches the committed events to
+/// `queries` as `CqrsFramework` would, and returns a receipt for the first transaction it
+/// posted, or `None` if the command moved no money.
+pub async fn execute_with_receipt<ES>(
+    store: &ES,
+    queries: &[Box<dyn Query<BankAccount>>],
+    services: &BankAccountServices,
+    aggregate_id: &str,
+    command: BankAccountCommand,
+    metadata: HashMap<String, String>,
+) -> Result<Option<Receipt>, AggregateError<BankAccountError>>
+where
+    ES: EventStore<BankAccount>,
+{
+    let context = store.load_aggregate(aggregate_id).await?;
+    let events = context
+        .aggregate()
+        .handle(command, services)
+        .await
+        .map_err(AggregateError::UserError)?;
+    let committed = store.commit(events, context, metadata).await?;
+    for query in queries {
+        query.dispatch(aggregate_id, &committed).await;
+    }
+
+    let transaction = committed.iter().find_map(|envelope| {
+        let entry = LedgerEntry::from_event(&envelope.payload)?;
+        let balance = balance_after(&envelope.payload)?;
+        Some((envelope, entry, balance))
+    });
+    let (envelope, entry, balance) = match transaction {
+        Some(transaction) => transaction,
+        None => return Ok(None),
+    };
+
+    Ok(Some(Receipt {
+        transaction_id: format!("{}-{}", envelope.aggregate_id, envelope.sequence),
+        timestamp: envelope.metadata.get(TIMESTAMP_METADATA_KEY).cloned(),
+        amount: entry.amount,
+        balance_after: balance,
+        description: entry.description,
+    }))
+}
+
+/// The balance recorded on a ledger event, before any later event in the same command
+/// moves it again, or `None` for an event that records no balance.
+fn balance_after(event: &BankAccountEvent) -> Option<f64> {
+    match event {
+        BankAccountEvent::CustomerDepositedMoney { balance, .. }
+        | BankAccountEvent::CustomerWithdrewCash { balance, .. }
+        | BankAccountEvent::CustomerWroteCheck { balance, .. }
+        | BankAccountEvent::BillPaid { balance, .. }
+        | BankAccountEvent::BonusApplied { balance, .. }
+        | BankAccountEvent::WireReceived { balance, .. }
+        | BankAccountEvent::CustomerTransferredMoney { balance, .. }
+        | BankAccountEvent::ChargeCaptured { balance, .. }
+        | BankAccountEvent::BackdatedTransactionPosted { balance, .. }
+        | BankAccountEvent::DisputeResolved { balance, .. }
+        | BankAccountEvent::InterestAccrued { balance, .. }
+        | BankAccountEvent::InterestCorrected { balance, .. }
+        | BankAccountEvent::BalanceAbsorbed { balance, .. }
+        | BankAccountEvent::AccountSpunOff { balance, .. }
+        | BankAccountEvent::TellerDeposited { balance, .. }
+        | BankAccountEvent::TellerWithdrew { balance, .. }
+        | BankAccountEvent::TaxWithheld { balance, .. }
+        | BankAccountEvent::TransactionReversed { balance, .. } => Some(*balance),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::{default_queries, DefaultViews};
+    use crate::PositiveAmount;
+    use cqrs_es::mem_store::MemStore;
+    use cqrs_es::persist::ViewRepository;
+
+    #[tokio::test]
+    async fn test_deposit_receipt() {
+        let store = MemStore::<BankAccount>::default();
+        let services = BankAccountServices::default();
+        let metadata = HashMap::from([(
+            TIMESTAMP_METADATA_KEY.to_string(),
+            "2023-05-31T09:00:00Z".to_string(),
+        )]);
+
+        let receipt = execute_with_receipt(
+            &store,
+            &[],
+            &services,
+            "acct-1",
+            BankAccountCommand::DepositMoney {
+                amount: PositiveAmount::new(200.0).unwrap(),
+            },
+            metadata,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            receipt,
+            Some(Receipt {
+                transaction_id: "acct-1-1".to_string(),
+                timestamp: Some("2023-05-31T09:00:00Z".to_string()),
+                amount: 200.0,
+                balance_after: 200.0,
+                description: "deposit".to_string(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_receipt_without_transaction() {
+        let store = MemStore::<BankAccount>::default();
+        let services = BankAccountServices::default();
+
+        let receipt = execute_with_receipt(
+            &store,
+            &[],
+            &services,
+            "acct-1",
+            BankAccountCommand::SetRoundUp { enabled: true },
+            HashMap::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(receipt, None);
+    }
+
+    #[test]
+    fn test_no_balance_after_non_ledger_event() {
+        let event = BankAccountEvent::RoundUpChanged { enabled: true };
+
+        assert_eq!(balance_after(&event), None);
+    }
+
+    #[tokio::test]
+    async fn test_receipt_balance_before_round_up() {
+        let store = MemStore::<BankAccount>::default();
+        let views = DefaultViews::default();
+        let queries = default_queries(&views);
+        let services = BankAccountServices::default();
+        for command in [
+            BankAccountCommand::SetRoundUp { enabled: true },
+            BankAccountCommand::DepositMoney {
+                amount: PositiveAmount::new(100.0).unwrap(),
+            },
+        ] {
+            execute_with_receipt(
+                &store,
+                &queries,
+                &services,
+                "acct-1",
+                command,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let receipt = execute_with_receipt(
+            &store,
+            &queries,
+            &services,
+            "acct-1",
+            BankAccountCommand::WithdrawMoney {
+                amount: PositiveAmount::new(10.5).unwrap(),
+                category: None,
+                mcc: None,
+            },
+            HashMap::new(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(receipt.amount, -10.5);
+        assert_eq!(receipt.balance_after, 89.5);
+        let recent_activity = views.recent_activity.load("acct-1").await.unwrap().unwrap();
+        // The round-up is set aside within the balance, so it posts no transaction.
+        assert_eq!(recent_activity.recent().len(), 2);
+    }
+}
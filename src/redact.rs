@@ -0,0 +1,356 @@
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::BankAccountEvent;
+
+/// The numeric fields that are not money. Every other number is masked, so a monetary field
+/// added to an event is redacted without being listed here.
+const NON_MONETARY_FIELDS: [&str; 10] = [
+    "maxHolds",
+    "intervalDays",
+    "originalSequence",
+    "taxYear",
+    "ratio",
+    "annualRate",
+    "points",
+    "total",
+    // `SystemTime` timestamps.
+    "secs_since_epoch",
+    "nanos_since_epoch",
+];
+const REDACTED: &str = "***";
+
+/// Serializes an event with every number outside `NON_MONETARY_FIELDS` masked, for use in log sinks.
+pub struct RedactedEvent<'a>(&'a BankAccountEvent);
+
+impl BankAccountEvent {
+    pub fn redacted(&self) -> RedactedEvent<'_> {
+        RedactedEvent(self)
+    }
+}
+
+impl Serialize for RedactedEvent<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(self.0).map_err(serde::ser::Error::custom)?;
+        redact(&mut value);
+        // A rejection's reason is error text, which may quote the amount that was refused.
+        if let Some(Value::String(reason)) = value.pointer_mut("/CommandRejected/reason") {
+            *reason = redact_numbers(reason);
+        }
+        value.serialize(serializer)
+    }
+}
+
+/// `text` with every run of digits, including any decimal point within it, masked.
+fn redact_numbers(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            while chars.next_if(|c| c.is_ascii_digit() || *c == '.').is_some() {}
+            redacted.push_str(REDACTED);
+        } else {
+            redacted.push(c);
+        }
+    }
+    redacted
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Number(_) => *value = Value::String(REDACTED.to_string()),
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if !NON_MONETARY_FIELDS.contains(&name.as_str()) {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    use serde_json::json;
+
+    use crate::date::Date;
+
+    #[test]
+    fn test_redacted_deposit() {
+        let event = BankAccountEvent::CustomerDepositedMoney {
+            amount: 200.0,
+            balance: 200.0,
+        };
+
+        let redacted = serde_json::to_value(event.redacted()).unwrap();
+
+        assert_eq!(
+            redacted,
+            json!({
+                "CustomerDepositedMoney": {
+                    "amount": "***",
+                    "balance": "***",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_redacted_keeps_non_monetary_fields() {
+        let event = BankAccountEvent::CustomerWroteCheck {
+            check_number: "1170".to_string(),
+            amount: 50.0,
+            balance: 150.0,
+        };
+
+        let redacted = serde_json::to_value(event.redacted()).unwrap();
+
+        assert_eq!(redacted["CustomerWroteCheck"]["checkNumber"], "1170");
+        assert_eq!(redacted["CustomerWroteCheck"]["amount"], "***");
+    }
+
+    #[test]
+    fn test_redacted_keeps_non_monetary_numbers() {
+        let event = BankAccountEvent::InterestCorrected {
+            original_sequence: 7,
+            original_amount: 12.5,
+            corrected_amount: 10.0,
+            reason: "rate error".to_string(),
+            balance: 110.0,
+        };
+
+        let redacted = serde_json::to_value(event.redacted()).unwrap();
+
+        assert_eq!(
+            redacted,
+            json!({
+                "InterestCorrected": {
+                    "originalSequence": 7,
+                    "originalAmount": "***",
+                    "correctedAmount": "***",
+                    "reason": "rate error",
+                    "balance": "***",
+                }
+            })
+        );
+
+        let event = BankAccountEvent::GarnishmentPlaced {
+            case_number: "CV-2023-118".to_string(),
+            amount: 75.0,
+            placed_at: SystemTime::UNIX_EPOCH,
+        };
+
+        let redacted = serde_json::to_value(event.redacted()).unwrap();
+
+        assert_eq!(
+            redacted["GarnishmentPlaced"]["placedAt"],
+            json!({ "secs_since_epoch": 0, "nanos_since_epoch": 0 })
+        );
+        assert_eq!(redacted["GarnishmentPlaced"]["amount"], "***");
+    }
+
+    #[test]
+    fn test_redacted_rejection_reason() {
+        let event = BankAccountEvent::CommandRejected {
+            command_type: "WithdrawMoney".to_string(),
+            reason: "funds not available, shortfall: 1224.5".to_string(),
+        };
+
+        let redacted = serde_json::to_value(event.redacted()).unwrap();
+
+        assert_eq!(
+            redacted,
+            json!({
+                "CommandRejected": {
+                    "commandType": "WithdrawMoney",
+                    "reason": "funds not available, shortfall: ***",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_redacts_every_monetary_event() {
+        const MONEY: f64 = 123.45;
+        let events = vec![
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::CustomerWithdrewCash {
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::CustomerWroteCheck {
+                check_number: "1170".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::CategoryBudgetSet {
+                category: "dining".to_string(),
+                limit: MONEY,
+            },
+            BankAccountEvent::CategorySpendRecorded {
+                category: "dining".to_string(),
+                amount: MONEY,
+                date: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+            BankAccountEvent::BalanceEstablished { balance: MONEY },
+            BankAccountEvent::BillPaymentScheduled {
+                payee: "power-co".to_string(),
+                amount: MONEY,
+                due: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+            BankAccountEvent::BillPaid {
+                payee: "power-co".to_string(),
+                amount: MONEY,
+                due: Date::from_ymd(2023, 5, 31).unwrap(),
+                balance: MONEY,
+            },
+            BankAccountEvent::BonusApplied {
+                bonus_id: "promo".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::WireReceived {
+                reference: "FED-1".to_string(),
+                amount: MONEY,
+                sender: "Acme Corp".to_string(),
+                balance: MONEY,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "auth-1".to_string(),
+                amount: MONEY,
+                placed_at: SystemTime::UNIX_EPOCH,
+            },
+            BankAccountEvent::CustomerTransferredMoney {
+                to_account_id: "acct-2".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::ChargeCaptured {
+                auth_id: "auth-1".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+                captured_at: SystemTime::UNIX_EPOCH,
+            },
+            BankAccountEvent::PaycheckAllocated {
+                bucket: "savings".to_string(),
+                amount: MONEY,
+            },
+            BankAccountEvent::BackdatedTransactionPosted {
+                amount: MONEY,
+                effective_date: Date::from_ymd(2023, 5, 31).unwrap(),
+                reason: "missed fee".to_string(),
+                balance: MONEY,
+            },
+            BankAccountEvent::BalanceAbsorbed {
+                from_account_id: "acct-2".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::InterestAccrued {
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::RoundUpSaved {
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::TransactionLimitSet {
+                max_transaction: MONEY,
+            },
+            BankAccountEvent::BucketDissolved {
+                name: "savings".to_string(),
+                amount: MONEY,
+            },
+            BankAccountEvent::InterestCorrected {
+                original_sequence: 1,
+                original_amount: MONEY,
+                corrected_amount: MONEY,
+                reason: "rate error".to_string(),
+                balance: MONEY,
+            },
+            BankAccountEvent::DisputedFundsHeld {
+                dispute_id: "dispute-1".to_string(),
+                amount: MONEY,
+                placed_at: SystemTime::UNIX_EPOCH,
+            },
+            BankAccountEvent::DisputeResolved {
+                dispute_id: "dispute-1".to_string(),
+                in_customer_favor: false,
+                amount: MONEY,
+                balance: MONEY,
+                resolved_at: SystemTime::UNIX_EPOCH,
+            },
+            BankAccountEvent::MerchantSpendRecorded {
+                mcc: "5812".to_string(),
+                amount: MONEY,
+            },
+            BankAccountEvent::SweepConfigured {
+                target_balance: MONEY,
+                sweep_to: "invest-1".to_string(),
+            },
+            BankAccountEvent::BalanceSnapshotted {
+                balance: MONEY,
+                as_of: SystemTime::UNIX_EPOCH,
+            },
+            BankAccountEvent::TransactionReversed {
+                original_sequence: 1,
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::TaxWithheld {
+                amount: MONEY,
+                balance: MONEY,
+                tax_year: 2023,
+            },
+            BankAccountEvent::Redenominated {
+                old_currency: None,
+                new_currency: "NEW".to_string(),
+                ratio: 0.001,
+                balance: MONEY,
+            },
+            BankAccountEvent::AccountSpunOff {
+                new_account_id: "acct-2".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::TellerDeposited {
+                teller_id: "teller-1".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::TellerWithdrew {
+                teller_id: "teller-1".to_string(),
+                amount: MONEY,
+                balance: MONEY,
+            },
+            BankAccountEvent::GarnishmentPlaced {
+                case_number: "CV-2023-118".to_string(),
+                amount: MONEY,
+                placed_at: SystemTime::UNIX_EPOCH,
+            },
+            BankAccountEvent::RecurringDepositScheduled {
+                schedule_id: "auto-save".to_string(),
+                amount: MONEY,
+                interval_days: 7,
+                next_run: Date::from_ymd(2023, 5, 31).unwrap(),
+            },
+            BankAccountEvent::CommandRejected {
+                command_type: "DepositMoney".to_string(),
+                reason: format!("transaction too large, limit: {}", MONEY),
+            },
+        ];
+
+        for event in events {
+            let redacted = serde_json::to_string(&event.redacted()).unwrap();
+            assert!(!redacted.contains("123.45"), "{}", redacted);
+        }
+    }
+}
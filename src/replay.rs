@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+use cqrs_es::Aggregate;
+
+use crate::{BankAccount, BankAccountEvent};
+
+/// A cap on replayed stream length generous enough for any legitimate account history.
+pub const DEFAULT_MAX_EVENTS: usize = 100_000;
+
+/// The stream held more than `max_events` events, so replay was abandoned.
+#[derive(Debug, PartialEq)]
+pub struct ReplayError {
+    pub max_events: usize,
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "event stream exceeds {} events", self.max_events)
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Folds `events` into an account, stopping with an error as soon as the stream turns out to
+/// be longer than `max_events` rather than consuming the rest of it.
+pub fn safe_replay<I>(events: I, max_events: usize) -> Result<BankAccount, ReplayError>
+where
+    I: IntoIterator<Item = BankAccountEvent>,
+{
+    let mut account = BankAccount::default();
+    for (index, event) in events.into_iter().enumerate() {
+        if index >= max_events {
+            return Err(ReplayError { max_events });
+        }
+        account.apply(event);
+    }
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposits() -> impl Iterator<Item = BankAccountEvent> {
+        (1..).map(|n| BankAccountEvent::CustomerDepositedMoney {
+            amount: 10.0,
+            balance: 10.0 * f64::from(n),
+        })
+    }
+
+    #[test]
+    fn test_replay_within_cap() {
+        let account = safe_replay(deposits().take(3), 3).unwrap();
+
+        assert_eq!(account.ledger_balance(), 30.0);
+    }
+
+    #[test]
+    fn test_replay_exceeding_cap() {
+        // The stream is unbounded; the guard must stop before trying to fold all of it.
+        let result = safe_replay(deposits(), DEFAULT_MAX_EVENTS);
+
+        assert_eq!(
+            result.err(),
+            Some(ReplayError {
+                max_events: DEFAULT_MAX_EVENTS
+            })
+        );
+    }
+}
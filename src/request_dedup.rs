@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use cqrs_es::{AggregateError, CqrsFramework, EventStore};
+
+use crate::clock::Clock;
+use crate::{BankAccount, BankAccountCommand, BankAccountError};
+
+/// Remembers recently seen request ids so a retried command within `window` is applied once.
+/// Ids older than the window are forgotten, keeping the set bounded by the request rate.
+pub struct RequestDedup {
+    window: Duration,
+    clock: Box<dyn Clock>,
+    seen: Mutex<HashMap<String, Seen>>,
+}
+
+/// A remembered request id: reserved while its command runs, then confirmed once it succeeds.
+enum Seen {
+    Pending,
+    At(SystemTime),
+}
+
+/// A request id reserved for a running command. Unless confirmed, dropping it releases the
+/// id, so a command that fails or whose future is cancelled can be retried.
+struct Reservation<'a> {
+    dedup: &'a RequestDedup,
+    request_id: &'a str,
+    confirmed: bool,
+}
+
+impl Reservation<'_> {
+    fn confirm(mut self, now: SystemTime) {
+        self.dedup.confirm(self.request_id, now);
+        self.confirmed = true;
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if !self.confirmed {
+            self.dedup.release(self.request_id);
+        }
+    }
+}
+
+impl RequestDedup {
+    pub fn new(window: Duration, clock: Box<dyn Clock>) -> Self {
+        Self {
+            window,
+            clock,
+            seen: Mutex::default(),
+        }
+    }
+
+    /// Records `request_id` as of `now`, returning false when it was already seen within the
+    /// window. Expired ids are pruned first.
+    pub fn first_seen_at(&self, request_id: &str, now: SystemTime) -> bool {
+        self.insert_if_unseen(request_id, now, Seen::At(now))
+            .unwrap_or(false)
+    }
+
+    /// Prunes expired ids and, under the same lock, stores `entry` for `request_id` unless it
+    /// was seen within the window ending at `now`, returning whether it was stored. An id
+    /// whose command is still running is an error, as its outcome isn't known yet.
+    fn insert_if_unseen(
+        &self,
+        request_id: &str,
+        now: SystemTime,
+        entry: Seen,
+    ) -> Result<bool, BankAccountError> {
+        let mut seen = self.seen.lock().unwrap();
+        seen.retain(|_, seen| match seen {
+            Seen::Pending => true,
+            Seen::At(seen_at) => now
+                .duration_since(*seen_at)
+                .map_or(true, |age| age < self.window),
+        });
+        match seen.get(request_id) {
+            Some(Seen::Pending) => Err("request in progress".into()),
+            Some(Seen::At(_)) => Ok(false),
+            None => {
+                seen.insert(request_id.to_string(), entry);
+                Ok(true)
+            }
+        }
+    }
+
+    fn confirm(&self, request_id: &str, now: SystemTime) {
+        let mut seen = self.seen.lock().unwrap();
+        seen.insert(request_id.to_string(), Seen::At(now));
+    }
+
+    fn release(&self, request_id: &str) {
+        self.seen.lock().unwrap().remove(request_id);
+    }
+
+    /// The number of request ids currently remembered.
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Executes `command` through `cqrs` unless `request_id` was seen within the window, in
+    /// which case the command is a no-op. A duplicate of a command that is still running is
+    /// rejected with "request in progress". The id is reserved before the command runs and
+    /// only kept once it succeeds, so a retry after a failure or cancellation is executed
+    /// again.
+    pub async fn execute<ES>(
+        &self,
+        cqrs: &CqrsFramework<BankAccount, ES>,
+        account_id: &str,
+        request_id: &str,
+        command: BankAccountCommand,
+    ) -> Result<(), AggregateError<BankAccountError>>
+    where
+        ES: EventStore<BankAccount>,
+    {
+        if !self
+            .insert_if_unseen(request_id, self.clock.now(), Seen::Pending)
+            .map_err(AggregateError::UserError)?
+        {
+            return Ok(());
+        }
+        let reservation = Reservation {
+            dedup: self,
+            request_id,
+            confirmed: false,
+        };
+        cqrs.execute(account_id, command).await?;
+        reservation.confirm(self.clock.now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use cqrs_es::mem_store::MemStore;
+    use cqrs_es::persist::ViewRepository;
+
+    use crate::clock::{FixedClock, SystemClock};
+    use crate::queries::{default_queries, DefaultViews};
+    use crate::{AtmError, BankAccountApi, BankAccountServices, CheckingError, PositiveAmount};
+
+    /// A check-validation service that yields once before answering, so a check being
+    /// written is still running when the next command is polled.
+    struct SlowCheckValidation;
+
+    #[async_trait]
+    impl BankAccountApi for SlowCheckValidation {
+        async fn atm_withdrawal(&self, _atm_id: &str, _amount: f64) -> Result<(), AtmError> {
+            Ok(())
+        }
+
+        async fn validate_check(&self, _account: &str, _check: &str) -> Result<(), CheckingError> {
+            tokio::task::yield_now().await;
+            Ok(())
+        }
+    }
+
+    /// A framework whose account holds 100 and validates checks slowly.
+    async fn funded_with_slow_checks(
+        views: &DefaultViews,
+    ) -> CqrsFramework<BankAccount, MemStore<BankAccount>> {
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            default_queries(views),
+            BankAccountServices::new(Box::new(SlowCheckValidation)),
+        );
+        cqrs.execute(
+            "acct-1",
+            BankAccountCommand::DepositMoney {
+                amount: PositiveAmount::new(100.0).unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+        cqrs
+    }
+
+    fn write_check() -> BankAccountCommand {
+        BankAccountCommand::WriteCheck {
+            check_number: "1170".to_string(),
+            amount: 10.0,
+            category: None,
+            mcc: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_within_window_is_a_no_op() {
+        let views = DefaultViews::default();
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            default_queries(&views),
+            BankAccountServices::default(),
+        );
+        let dedup = RequestDedup::new(
+            Duration::from_secs(60),
+            Box::new(FixedClock(SystemTime::UNIX_EPOCH)),
+        );
+        let deposit = || BankAccountCommand::DepositMoney {
+            amount: PositiveAmount::new(10.0).unwrap(),
+        };
+
+        for _ in 0..2 {
+            dedup
+                .execute(&cqrs, "acct-1", "req-1", deposit())
+                .await
+                .unwrap();
+        }
+
+        let activity = views.recent_activity.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(activity.recent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicates_execute_once() {
+        let views = DefaultViews::default();
+        let cqrs = funded_with_slow_checks(&views).await;
+        let dedup = RequestDedup::new(
+            Duration::from_secs(60),
+            Box::new(FixedClock(SystemTime::UNIX_EPOCH)),
+        );
+
+        let (first, second) = tokio::join!(
+            dedup.execute(&cqrs, "acct-1", "req-1", write_check()),
+            dedup.execute(&cqrs, "acct-1", "req-1", write_check()),
+        );
+        first.unwrap();
+        // The duplicate can't know yet whether the first run will succeed, so it is not
+        // reported as done.
+        assert!(matches!(
+            second,
+            Err(AggregateError::UserError(BankAccountError(reason)))
+                if reason == "request in progress"
+        ));
+
+        let activity = views.recent_activity.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(activity.recent().len(), 2);
+        assert_eq!(dedup.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_request_is_released() {
+        let views = DefaultViews::default();
+        let cqrs = funded_with_slow_checks(&views).await;
+        let dedup = RequestDedup::new(
+            Duration::from_secs(60),
+            Box::new(FixedClock(SystemTime::UNIX_EPOCH)),
+        );
+
+        // Drop the command's future while it waits on check validation.
+        tokio::select! {
+            biased;
+            _ = dedup.execute(&cqrs, "acct-1", "req-1", write_check()) => {
+                panic!("check validation should still be running")
+            }
+            _ = std::future::ready(()) => {}
+        }
+        assert!(dedup.is_empty());
+
+        dedup
+            .execute(&cqrs, "acct-1", "req-1", write_check())
+            .await
+            .unwrap();
+        let activity = views.recent_activity.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(activity.recent().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_failure_is_executed() {
+        let views = DefaultViews::default();
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            default_queries(&views),
+            BankAccountServices::default(),
+        );
+        let dedup = RequestDedup::new(
+            Duration::from_secs(60),
+            Box::new(FixedClock(SystemTime::UNIX_EPOCH)),
+        );
+        let withdrawal = || BankAccountCommand::WithdrawMoney {
+            amount: PositiveAmount::new(10.0).unwrap(),
+            category: None,
+            mcc: None,
+        };
+
+        let result = dedup.execute(&cqrs, "acct-1", "req-1", withdrawal()).await;
+        assert!(matches!(result, Err(AggregateError::UserError(_))));
+        assert!(dedup.is_empty());
+
+        dedup
+            .execute(
+                &cqrs,
+                "acct-1",
+                "req-2",
+                BankAccountCommand::DepositMoney {
+                    amount: PositiveAmount::new(10.0).unwrap(),
+                },
+            )
+            .await
+            .unwrap();
+        dedup
+            .execute(&cqrs, "acct-1", "req-1", withdrawal())
+            .await
+            .unwrap();
+
+        let activity = views.recent_activity.load("acct-1").await.unwrap().unwrap();
+        assert_eq!(activity.recent().len(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_after_window_is_processed_again() {
+        let dedup = RequestDedup::new(Duration::from_secs(60), Box::new(SystemClock));
+        let start = SystemTime::UNIX_EPOCH;
+
+        assert!(dedup.first_seen_at("req-1", start));
+        assert!(!dedup.first_seen_at("req-1", start + Duration::from_secs(59)));
+        assert!(dedup.first_seen_at("req-1", start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_expired_requests_are_pruned() {
+        let dedup = RequestDedup::new(Duration::from_secs(60), Box::new(SystemClock));
+        let start = SystemTime::UNIX_EPOCH;
+
+        assert!(dedup.first_seen_at("req-1", start));
+        assert!(dedup.first_seen_at("req-2", start + Duration::from_secs(30)));
+        assert_eq!(dedup.len(), 2);
+
+        assert!(dedup.first_seen_at("req-3", start + Duration::from_secs(75)));
+        assert_eq!(dedup.len(), 2);
+        assert!(dedup.first_seen_at("req-1", start + Duration::from_secs(80)));
+        assert_eq!(dedup.len(), 3);
+    }
+}
@@ -0,0 +1,114 @@
+use serde::de::Error;
+use serde_json::Value;
+
+use crate::{BankAccount, SchemaVersion, CURRENT_SCHEMA_VERSION};
+
+/// Loads a `BankAccount` snapshot written by any schema version, upgrading it to the current one.
+pub fn migrate_snapshot(mut json: Value) -> Result<BankAccount, serde_json::Error> {
+    let version = json
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if version > u64::from(CURRENT_SCHEMA_VERSION) {
+        return Err(serde_json::Error::custom(format!(
+            "unsupported snapshot schema version: {}",
+            version
+        )));
+    }
+    if version == 0 {
+        upgrade_v0(&mut json)?;
+    }
+
+    let mut account: BankAccount = serde_json::from_value(json)?;
+    account.schema_version = SchemaVersion::default();
+    Ok(account)
+}
+
+/// v0 snapshots carry only `opened` and `balance`; every other field takes its default.
+fn upgrade_v0(json: &mut Value) -> Result<(), serde_json::Error> {
+    let snapshot = json
+        .as_object_mut()
+        .ok_or_else(|| serde_json::Error::custom("snapshot is not a JSON object"))?;
+    if let Value::Object(defaults) = serde_json::to_value(BankAccount::default())? {
+        for (field, value) in defaults {
+            snapshot.entry(field).or_insert(value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_v0_snapshot() {
+        let snapshot = json!({
+            "opened": true,
+            "balance": 250.0,
+        });
+
+        let account = migrate_snapshot(snapshot).unwrap();
+
+        assert!(account.opened);
+        assert_eq!(account.balance, 250.0);
+        assert_eq!(account.account_id, "");
+        assert!(account.budgets.is_empty());
+        assert!(account.scheduled_payments.is_empty());
+        assert_eq!(
+            account.schema_version,
+            SchemaVersion(CURRENT_SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_migrate_current_snapshot() {
+        let snapshot = serde_json::to_value(BankAccount::default()).unwrap();
+        assert_eq!(snapshot["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+
+        assert!(migrate_snapshot(snapshot).is_ok());
+    }
+
+    #[test]
+    fn test_migrate_early_v1_snapshot() {
+        // Every field a v1 snapshot had when the version was introduced.
+        let snapshot = json!({
+            "schema_version": 1,
+            "account_id": "acct-1",
+            "opened": true,
+            "event_count": 3,
+            "balance": 250.0,
+            "budgets": {
+                "groceries": { "limit": 400.0, "spent": 120.0, "month": [2023, 5] },
+            },
+            "scheduled_payments": [
+                { "payee": "electric", "amount": 80.0, "due": "2023-06-01" },
+            ],
+            "applied_bonuses": ["welcome"],
+        });
+
+        let account = migrate_snapshot(snapshot).unwrap();
+
+        assert!(account.opened);
+        assert_eq!(account.account_id, "acct-1");
+        assert_eq!(account.balance, 250.0);
+        assert_eq!(account.budgets.len(), 1);
+        assert_eq!(account.scheduled_payments.len(), 1);
+        assert!(account.applied_bonuses.contains("welcome"));
+        assert!(!account.closed);
+        assert!(account.holds.is_empty());
+        assert_eq!(account.currency, None);
+    }
+
+    #[test]
+    fn test_migrate_future_snapshot() {
+        let snapshot = json!({
+            "schema_version": CURRENT_SCHEMA_VERSION + 1,
+            "opened": true,
+            "balance": 250.0,
+        });
+
+        assert!(migrate_snapshot(snapshot).is_err());
+    }
+}
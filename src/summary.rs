@@ -0,0 +1,117 @@
+use serde::Serialize;
+
+use crate::BankAccount;
+
+/// A single-struct view of an account's current state, for API responses.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AccountSummary {
+    pub account_id: String,
+    pub nickname: Option<String>,
+    pub branch_id: Option<String>,
+    pub currency: Option<String>,
+    pub balance: f64,
+    pub available_balance: f64,
+    /// Everything keeping the balance from being available: holds, scheduled payments and
+    /// garnishments.
+    pub total_held: f64,
+    pub garnished: f64,
+    pub opened: bool,
+    pub closed: bool,
+    pub kyc_verified: bool,
+    pub under_review: bool,
+}
+
+impl BankAccount {
+    pub fn summarize(&self) -> AccountSummary {
+        AccountSummary {
+            account_id: self.account_id.clone(),
+            nickname: self.nickname.clone(),
+            branch_id: self.branch_id.clone(),
+            currency: self.currency.clone(),
+            balance: self.ledger_balance(),
+            available_balance: self.available_balance(),
+            total_held: self.ledger_balance() - self.available_balance(),
+            garnished: self.garnished(),
+            opened: self.opened,
+            closed: self.closed,
+            kyc_verified: self.kyc_verified,
+            under_review: self.review.is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::Date;
+    use crate::{BankAccountEvent, ReviewSeverity};
+    use cqrs_es::Aggregate;
+
+    #[test]
+    fn test_summarize() {
+        let placed_at = Date::from_ymd(2023, 5, 31).unwrap().start_of_day();
+        let mut account = BankAccount::default();
+        for event in [
+            BankAccountEvent::AccountOpened {
+                account_id: "acct-1".to_string(),
+                max_holds: None,
+                first_withdrawal_allowed_after: None,
+                branch_id: Some("branch-1".to_string()),
+            },
+            BankAccountEvent::KycVerified {
+                verification_id: "kyc-1".to_string(),
+            },
+            BankAccountEvent::NicknameChanged {
+                nickname: "Bills".to_string(),
+            },
+            BankAccountEvent::CustomerDepositedMoney {
+                amount: 500.0,
+                balance: 500.0,
+            },
+            BankAccountEvent::HoldPlaced {
+                hold_id: "hold-1".to_string(),
+                amount: 40.0,
+                placed_at,
+            },
+            BankAccountEvent::DisputedFundsHeld {
+                dispute_id: "dispute-1".to_string(),
+                amount: 60.0,
+                placed_at,
+            },
+            BankAccountEvent::BillPaymentScheduled {
+                payee: "power-co".to_string(),
+                amount: 20.0,
+                due: Date::from_ymd(2023, 6, 1).unwrap(),
+            },
+            BankAccountEvent::GarnishmentPlaced {
+                case_number: "CV-2023-118".to_string(),
+                amount: 30.0,
+                placed_at,
+            },
+            BankAccountEvent::FlaggedForReview {
+                reason: "unusual velocity".to_string(),
+                severity: ReviewSeverity::Low,
+            },
+        ] {
+            account.apply(event);
+        }
+
+        assert_eq!(
+            account.summarize(),
+            AccountSummary {
+                account_id: "acct-1".to_string(),
+                nickname: Some("Bills".to_string()),
+                branch_id: Some("branch-1".to_string()),
+                currency: None,
+                balance: 500.0,
+                available_balance: 350.0,
+                total_held: 150.0,
+                garnished: 30.0,
+                opened: true,
+                closed: false,
+                kyc_verified: true,
+                under_review: true,
+            }
+        );
+    }
+}
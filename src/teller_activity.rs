@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use cqrs_es::{EventEnvelope, Query};
+
+use crate::{BankAccount, BankAccountEvent};
+
+/// Net cash each teller's drawer took in across all accounts, for end-of-day drawer
+/// reconciliation: deposits add to the drawer and withdrawals pay out of it. Reversing a
+/// teller transaction, as `VoidDay` does, moves the drawer back with the account.
+#[derive(Default)]
+pub struct TellerActivityQuery {
+    net_cash: Mutex<BTreeMap<String, f64>>,
+    /// The teller behind each teller transaction, by account and sequence.
+    tellers: Mutex<BTreeMap<(String, usize), String>>,
+}
+
+impl TellerActivityQuery {
+    pub fn net_cash(&self, teller_id: &str) -> f64 {
+        let net_cash = self.net_cash.lock().unwrap();
+        net_cash.get(teller_id).copied().unwrap_or(0.0)
+    }
+}
+
+#[async_trait]
+impl Query<BankAccount> for TellerActivityQuery {
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<BankAccount>]) {
+        let mut net_cash = self.net_cash.lock().unwrap();
+        let mut tellers = self.tellers.lock().unwrap();
+        for event in events {
+            let key = (aggregate_id.to_string(), event.sequence);
+            let (teller_id, amount) = match &event.payload {
+                BankAccountEvent::TellerDeposited {
+                    teller_id, amount, ..
+                } => {
+                    tellers.insert(key, teller_id.clone());
+                    (teller_id.clone(), *amount)
+                }
+                BankAccountEvent::TellerWithdrew {
+                    teller_id, amount, ..
+                } => {
+                    tellers.insert(key, teller_id.clone());
+                    (teller_id.clone(), -amount)
+                }
+                // A reversal's amount is signed against the account, as the drawer moves.
+                BankAccountEvent::TransactionReversed {
+                    original_sequence,
+                    amount,
+                    ..
+                } => match tellers.get(&(aggregate_id.to_string(), *original_sequence)) {
+                    Some(teller_id) => (teller_id.clone(), *amount),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            *net_cash.entry(teller_id).or_default() += amount;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn envelope(
+        aggregate_id: &str,
+        sequence: usize,
+        payload: BankAccountEvent,
+    ) -> EventEnvelope<BankAccount> {
+        EventEnvelope {
+            aggregate_id: aggregate_id.to_string(),
+            sequence,
+            payload,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_net_cash_per_teller() {
+        let query = TellerActivityQuery::default();
+        let transactions = [
+            ("acct-1", 1, "teller-1", 500.0, true),
+            ("acct-2", 1, "teller-1", 200.0, true),
+            ("acct-1", 2, "teller-1", 150.0, false),
+            ("acct-2", 2, "teller-2", 80.0, false),
+        ];
+
+        for (account_id, sequence, teller_id, amount, deposit) in transactions {
+            let teller_id = teller_id.to_string();
+            let payload = if deposit {
+                BankAccountEvent::TellerDeposited {
+                    teller_id,
+                    amount,
+                    balance: amount,
+                }
+            } else {
+                BankAccountEvent::TellerWithdrew {
+                    teller_id,
+                    amount,
+                    balance: 0.0,
+                }
+            };
+            query
+                .dispatch(account_id, &[envelope(account_id, sequence, payload)])
+                .await;
+        }
+
+        assert_eq!(query.net_cash("teller-1"), 550.0);
+        assert_eq!(query.net_cash("teller-2"), -80.0);
+        assert_eq!(query.net_cash("teller-3"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_reversal_returns_cash_to_the_drawer() {
+        let query = TellerActivityQuery::default();
+        let events = [
+            envelope(
+                "acct-1",
+                1,
+                BankAccountEvent::TellerDeposited {
+                    teller_id: "teller-1".to_string(),
+                    amount: 500.0,
+                    balance: 500.0,
+                },
+            ),
+            envelope(
+                "acct-1",
+                2,
+                BankAccountEvent::TellerWithdrew {
+                    teller_id: "teller-1".to_string(),
+                    amount: 150.0,
+                    balance: 350.0,
+                },
+            ),
+            envelope(
+                "acct-1",
+                3,
+                BankAccountEvent::CustomerDepositedMoney {
+                    amount: 100.0,
+                    balance: 450.0,
+                },
+            ),
+            envelope(
+                "acct-1",
+                4,
+                BankAccountEvent::TransactionReversed {
+                    original_sequence: 3,
+                    amount: -100.0,
+                    balance: 350.0,
+                },
+            ),
+            envelope(
+                "acct-1",
+                5,
+                BankAccountEvent::TransactionReversed {
+                    original_sequence: 2,
+                    amount: 150.0,
+                    balance: 500.0,
+                },
+            ),
+        ];
+        query.dispatch("acct-1", &events).await;
+        assert_eq!(query.net_cash("teller-1"), 500.0);
+
+        // The same sequence on another account is a different transaction.
+        query
+            .dispatch(
+                "acct-2",
+                &[envelope(
+                    "acct-2",
+                    6,
+                    BankAccountEvent::TransactionReversed {
+                        original_sequence: 1,
+                        amount: -500.0,
+                        balance: 0.0,
+                    },
+                )],
+            )
+            .await;
+        assert_eq!(query.net_cash("teller-1"), 500.0);
+
+        query
+            .dispatch(
+                "acct-1",
+                &[envelope(
+                    "acct-1",
+                    6,
+                    BankAccountEvent::TransactionReversed {
+                        original_sequence: 1,
+                        amount: -500.0,
+                        balance: 0.0,
+                    },
+                )],
+            )
+            .await;
+        assert_eq!(query.net_cash("teller-1"), 0.0);
+    }
+}
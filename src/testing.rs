@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use cqrs_es::test::AggregateResultValidator;
+use cqrs_es::{EventEnvelope, View};
+
+use crate::date::Date;
+use crate::queries::TIMESTAMP_METADATA_KEY;
+use crate::{BankAccount, BankAccountEvent};
+
+/// View assertions for `TestFramework` results.
+pub trait ExpectView {
+    /// Folds the resulting events into a default view and compares it with `expected`.
+    fn then_expect_view<V>(self, expected: V)
+    where
+        V: View<BankAccount> + PartialEq;
+
+    /// Folds the resulting events into `initial` and compares it with `expected`.
+    fn then_expect_view_from<V>(self, initial: V, expected: V)
+    where
+        V: View<BankAccount> + PartialEq;
+}
+
+impl ExpectView for AggregateResultValidator<BankAccount> {
+    fn then_expect_view<V>(self, expected: V)
+    where
+        V: View<BankAccount> + PartialEq,
+    {
+        self.then_expect_view_from(V::default(), expected)
+    }
+
+    fn then_expect_view_from<V>(self, initial: V, expected: V)
+    where
+        V: View<BankAccount> + PartialEq,
+    {
+        let events = match self.inspect_result() {
+            Ok(events) => events,
+            Err(err) => panic!("expected success, received aggregate error: '{}'", err),
+        };
+        let mut view = initial;
+        for (index, payload) in events.into_iter().enumerate() {
+            view.update(&EventEnvelope {
+                aggregate_id: String::new(),
+                sequence: index + 1,
+                payload,
+                metadata: HashMap::new(),
+            });
+        }
+        assert_eq!(view, expected);
+    }
+}
+
+/// Drives a view with events as if each were committed at the paired time, carrying the
+/// `timestamp` metadata that `TestFramework` does not supply.
+pub struct TimedReplay {
+    events: Vec<(SystemTime, BankAccountEvent)>,
+}
+
+impl TimedReplay {
+    pub fn new(events: Vec<(SystemTime, BankAccountEvent)>) -> Self {
+        Self { events }
+    }
+
+    pub fn replay<V: View<BankAccount>>(self) -> V {
+        let mut view = V::default();
+        for (index, (committed_at, payload)) in self.events.into_iter().enumerate() {
+            let metadata =
+                HashMap::from([(TIMESTAMP_METADATA_KEY.to_string(), rfc3339(committed_at))]);
+            view.update(&EventEnvelope {
+                aggregate_id: String::new(),
+                sequence: index + 1,
+                payload,
+                metadata,
+            });
+        }
+        view
+    }
+}
+
+/// `time` as an RFC 3339 UTC timestamp, to whole seconds.
+fn rfc3339(time: SystemTime) -> String {
+    let date = Date::from_system_time(time);
+    let seconds = time
+        .duration_since(date.start_of_day())
+        .unwrap_or_default()
+        .as_secs();
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        date,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use cqrs_es::persist::{PersistenceError, ViewContext, ViewRepository};
+use cqrs_es::{Aggregate, View};
+use serde_json::Value;
+
+/// An in-memory `ViewRepository`, suitable for local testing.
+///
+/// Views are stored serialized, the same way a database-backed repository would hold them.
+pub struct MemViewRepository<V> {
+    views: RwLock<HashMap<String, (Value, i64)>>,
+    phantom: PhantomData<V>,
+}
+
+impl<V> Default for MemViewRepository<V> {
+    fn default() -> Self {
+        Self {
+            views: RwLock::default(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<V, A> ViewRepository<V, A> for MemViewRepository<V>
+where
+    V: View<A>,
+    A: Aggregate,
+{
+    async fn load(&self, view_id: &str) -> Result<Option<V>, PersistenceError> {
+        Ok(self.load_with_context(view_id).await?.map(|(view, _)| view))
+    }
+
+    async fn load_with_context(
+        &self,
+        view_id: &str,
+    ) -> Result<Option<(V, ViewContext)>, PersistenceError> {
+        // uninteresting unwrap: a poisoned lock means a writer panicked mid-update
+        let views = self.views.read().unwrap();
+        match views.get(view_id) {
+            None => Ok(None),
+            Some((payload, version)) => {
+                let view = serde_json::from_value(payload.clone())
+                    .map_err(|err| PersistenceError::DeserializationError(Box::new(err)))?;
+                Ok(Some((
+                    view,
+                    ViewContext::new(view_id.to_string(), *version),
+                )))
+            }
+        }
+    }
+
+    async fn update_view(&self, view: V, context: ViewContext) -> Result<(), PersistenceError> {
+        let payload = serde_json::to_value(&view)
+            .map_err(|err| PersistenceError::UnknownError(Box::new(err)))?;
+        let mut views = self.views.write().unwrap();
+        let current_version = views.get(&context.view_instance_id).map(|(_, v)| *v);
+        if current_version.unwrap_or(0) != context.version {
+            return Err(PersistenceError::OptimisticLockError);
+        }
+        views.insert(context.view_instance_id, (payload, context.version + 1));
+        Ok(())
+    }
+}
+
+/// Loads the view for `account_id` from any `ViewRepository`, falling back to the empty
+/// view for an account that has no events yet.
+pub async fn load_view<V, A, R>(repository: &R, account_id: &str) -> Result<V, PersistenceError>
+where
+    V: View<A>,
+    A: Aggregate,
+    R: ViewRepository<V, A>,
+{
+    Ok(repository.load(account_id).await?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use cqrs_es::mem_store::MemStore;
+    use cqrs_es::persist::GenericQuery;
+    use cqrs_es::CqrsFramework;
+
+    use crate::queries::{LedgerEntry, RecentActivityView};
+    use crate::{BankAccount, BankAccountCommand, BankAccountServices, PositiveAmount};
+
+    #[tokio::test]
+    async fn test_load_persisted_view() {
+        let repository = Arc::new(MemViewRepository::<RecentActivityView>::default());
+        let cqrs = CqrsFramework::new(
+            MemStore::<BankAccount>::default(),
+            vec![Box::new(GenericQuery::new(Arc::clone(&repository)))],
+            BankAccountServices::default(),
+        );
+
+        cqrs.execute(
+            "acct-1",
+            BankAccountCommand::DepositMoney {
+                amount: PositiveAmount::new(200.0).unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let view: RecentActivityView = load_view(repository.as_ref(), "acct-1").await.unwrap();
+        assert_eq!(
+            view.recent(),
+            &[LedgerEntry {
+                description: "deposit".to_string(),
+                amount: 200.0,
+            }]
+        );
+
+        let empty: RecentActivityView = load_view(repository.as_ref(), "acct-2").await.unwrap();
+        assert!(empty.recent().is_empty());
+    }
+}